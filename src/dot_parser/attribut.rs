@@ -1,11 +1,13 @@
-use super::parsing_error::ParsingError;
+use std::fmt::Display;
+
+use super::{attribut_value::AttributValue, parser::Parser, parsing_error::ParsingError};
 
 #[derive(PartialEq, Eq,Debug, Clone)]
-pub struct Attribut{pub key: String,pub value: String}
+pub struct Attribut{pub key: String,pub value: AttributValue}
 
-impl ToString for Attribut {
-    fn to_string(&self) -> String {
-        format!("{}:{}", self.key, self.value)
+impl Display for Attribut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.key, self.value)
     }
 }
 
@@ -13,9 +15,7 @@ impl TryFrom<&str> for Attribut {
     type Error = ParsingError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let splitted = value.trim().split_once("=")
-            .ok_or(ParsingError::DefaultError("Could not parse Attribute: ".to_string() + value))?;
-        Ok(Self{key: splitted.0.to_string(), value:splitted.1.to_string()})
+        Parser::new(value)?.parse_attribut()
     }
 }
 
@@ -28,9 +28,10 @@ mod tests {
     #[test]
     fn test_extract_attributes() {
         let combinations :Vec<(&str, Attribut)> = vec![
-            ("ata=3", Attribut{ key:"ata".to_string(), value: "3".to_string()}),
-            ("encore=\"encore\"", Attribut{ key:"encore".to_string(),value: "\"encore\"".to_string()}),
-            ("2=label", Attribut{ key:"2".to_string(), value: "label".to_string()}),
+            ("ata=3", Attribut{ key:"ata".to_string(), value: "3".into()}),
+            ("encore=\"encore\"", Attribut{ key:"encore".to_string(),value: "encore".into()}),
+            ("\"my key\"=\"a \\\"b\\\"\"", Attribut{ key:"my key".to_string(),value: "a \"b\"".into()}),
+            ("2=label", Attribut{ key:"2".to_string(), value: "label".into()}),
         ];
             
         combinations.iter().for_each(|combinaisons|{
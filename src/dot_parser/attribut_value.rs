@@ -0,0 +1,159 @@
+use std::{borrow::Cow, fmt::Display};
+
+use super::lexer::{is_numeral, quote_id};
+
+// Value of an attribute, both as found in the source and as meant
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum AttributValue {
+    Id(String),
+    Numeral(String),
+    Quoted { raw: String, value: String },
+    // Content of an HTML-like string, without the outer '<' '>'
+    Html(String),
+}
+
+impl AttributValue {
+    // Text as found in the source, ready to be written back
+    pub fn raw(&self) -> Cow<'_, str> {
+        match self {
+            AttributValue::Id(value) | AttributValue::Numeral(value) => Cow::Borrowed(value),
+            AttributValue::Quoted { raw, .. } => Cow::Borrowed(raw),
+            AttributValue::Html(value) => Cow::Owned(format!("<{}>", value)),
+        }
+    }
+
+    // Meaning of the value, without quotes, escapes nor concatenations
+    pub fn value(&self) -> &String {
+        match self {
+            AttributValue::Id(value) | AttributValue::Numeral(value) => value,
+            AttributValue::Quoted { value, .. } => value,
+            AttributValue::Html(value) => value,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.value()
+    }
+
+    // Numbers may be quoted, as in width="1.5"
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            AttributValue::Html(_) => None,
+            other => other.value().trim().parse().ok()
+        }
+    }
+
+    // Booleans as Graphviz reads them: true/yes, false/no, or an integer
+    pub fn as_bool(&self) -> Option<bool> {
+        if self.is_html() {
+            return None;
+        }
+        match self.value().trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" => Some(true),
+            "false" | "no" => Some(false),
+            other => other.parse::<i64>().ok().map(|number| number != 0)
+        }
+    }
+
+    pub fn is_html(&self) -> bool {
+        matches!(self, AttributValue::Html(_))
+    }
+}
+
+// "red" and red are the same value in DOT, <red> is not
+impl PartialEq for AttributValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_html() == other.is_html() && self.value() == other.value()
+    }
+}
+
+impl Eq for AttributValue {}
+
+// The value is kept as given, only its raw form is quoted and escaped. DOT has no way to write an odd number of
+// backslashes before a quote, a line break or the end of a string: such a value is written with one more backslash,
+// and reads back that way
+impl From<&str> for AttributValue {
+    fn from(value: &str) -> Self {
+        let raw = quote_id(value);
+        match raw == value {
+            true if is_numeral(value) => AttributValue::Numeral(raw),
+            true => AttributValue::Id(raw),
+            false => AttributValue::Quoted { raw, value: value.to_string() }
+        }
+    }
+}
+
+impl From<String> for AttributValue {
+    fn from(value: String) -> Self {
+        AttributValue::from(value.as_str())
+    }
+}
+
+impl Display for AttributValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.raw())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_value() {
+        let combinations :Vec<(&str, AttributValue)> = vec![
+            ("red", AttributValue::Id("red".to_string())),
+            ("1.5", AttributValue::Numeral("1.5".to_string())),
+            ("to B", AttributValue::Quoted { raw: "\"to B\"".to_string(), value: "to B".to_string() }),
+            ("say \"hi\"", AttributValue::Quoted { raw: "\"say \\\"hi\\\"\"".to_string(), value: "say \"hi\"".to_string() }),
+            ("C:\\", AttributValue::Quoted { raw: "\"C:\\\\\"".to_string(), value: "C:\\".to_string() }),
+            ];
+
+        combinations.iter().for_each(|combinaisons| {
+            let value = AttributValue::from(combinaisons.0);
+            assert_eq!(value.raw(), combinaisons.1.raw());
+            assert_eq!(value.value(), combinaisons.1.value());
+            assert_eq!(std::mem::discriminant(&value), std::mem::discriminant(&combinaisons.1));
+        });
+    }
+
+    #[test]
+    fn equality_ignores_quotes() {
+        assert_eq!(AttributValue::Quoted { raw: "\"red\"".to_string(), value: "red".to_string() }, AttributValue::from("red"));
+        assert_ne!(AttributValue::from("red"), AttributValue::from("blue"));
+        assert_ne!(AttributValue::Html("red".to_string()), AttributValue::from("red"));
+    }
+
+    #[test]
+    fn typed_accessors() {
+        let combinations :Vec<(AttributValue, Option<f64>, Option<bool>)> = vec![
+            (AttributValue::from("1.5"), Some(1.5), None),
+            (AttributValue::from("-2"), Some(-2.0), Some(true)),
+            (AttributValue::from("0"), Some(0.0), Some(false)),
+            (AttributValue::Quoted { raw: "\"0.75\"".to_string(), value: "0.75".to_string() }, Some(0.75), None),
+            (AttributValue::from("TRUE"), None, Some(true)),
+            (AttributValue::from("no"), None, Some(false)),
+            (AttributValue::from("red"), None, None),
+            (AttributValue::Html("1".to_string()), None, None),
+            ];
+
+        combinations.iter().for_each(|combinaisons| {
+            assert_eq!(combinaisons.0.as_f64(), combinaisons.1);
+            assert_eq!(combinaisons.0.as_bool(), combinaisons.2);
+        });
+        assert_eq!(AttributValue::from("to B").as_str(), "to B");
+    }
+
+    #[test]
+    fn html_raw() {
+        let value = AttributValue::Html("<b>bold</b>".to_string());
+        assert!(value.is_html());
+        assert_eq!(value.raw(), "<<b>bold</b>>");
+        assert_eq!(value.value(), "<b>bold</b>");
+        assert!(!AttributValue::from("<b>").is_html());
+    }
+}
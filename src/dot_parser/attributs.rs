@@ -1,43 +1,117 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
 
-use super::parsing_error::ParsingError;
+use super::{attribut::Attribut, attribut_value::AttributValue, lexer::quote_id, parser::Parser, parsing_error::ParsingError};
 
 
-#[derive(Default, PartialEq, Eq, Debug, Clone)]
+// Attributes in insertion order, so that written graphs are stable
+#[derive(Default, Debug, Clone)]
 #[cfg_attr(
     feature = "serde",
     derive(serde::Serialize, serde::Deserialize)
 )]
-pub struct Attributs(Option<HashMap<String,String>>);
+pub struct Attributs(Option<Vec<(String,AttributValue)>>);
 
 impl Attributs {
     pub fn label(&self) -> Option<&String> {
         self.get("label")
     }
 
+    // Decoded value of the attribute
     pub fn get(&self, key: &str) -> Option<&String> {
-        self.0.as_ref()
-            .map(|attributs| attributs.get(key))
-            .unwrap_or_default()
+        self.get_value(key).map(AttributValue::value)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get_value(key).map(AttributValue::as_str)
+    }
+
+    // Value of the attribute, with its source text
+    pub fn get_value(&self, key: &str) -> Option<&AttributValue> {
+        self.iter()
+            .find(|(id, _)| *id == key)
+            .map(|(_, value)| value)
+    }
+
+    // Attributes in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &AttributValue)> {
+        self.0.iter().flatten().map(|(key, value)| (key, value))
     }
 
     pub fn attributs(&self) -> Option<HashMap<String, String>> {
-        self.0.clone()
+        self.0.as_ref().map(|attributs| attributs.iter().map(|(key, value)| (key.clone(), value.value().clone())).collect())
     }
 
+    // Add the other attributes, overriding the ones already set in place
+    pub fn extend(&mut self, other: &Attributs) {
+        if let Some(other) = &other.0 {
+            let attributs = self.0.get_or_insert_with(Vec::new);
+            other.iter().for_each(|(key, value)| Attributs::insert(attributs, key.clone(), value.clone()));
+        }
+    }
+
+    // Set the attribute, in place when it is already set. The value is kept as given, see AttributValue::from for
+    // the backslashes DOT cannot write
+    pub fn set(&mut self, key: &str, value: impl Into<AttributValue>) {
+        Attributs::insert(self.0.get_or_insert_with(Vec::new), key.to_string(), value.into());
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<AttributValue> {
+        let attributs = self.0.as_mut()?;
+        let position = attributs.iter().position(|(id, _)| id == key)?;
+        let value = attributs.remove(position).1;
+        if attributs.is_empty() {
+            self.0 = None;
+        }
+        Some(value)
+    }
+
+    // Sort the attributes by key, for a canonical output
+    pub fn sort(&mut self) {
+        if let Some(attributs) = &mut self.0 {
+            attributs.sort_by(|first, second| first.0.cmp(&second.0));
+        }
+    }
+
+    fn insert(attributs: &mut Vec<(String, AttributValue)>, key: String, value: AttributValue) {
+        match attributs.iter_mut().find(|(id, _)| *id == key) {
+            Some(attribut) => attribut.1 = value,
+            None => attributs.push((key, value))
+        }
+    }
+
+}
+
+// Order does not matter, as for a set of attributes in DOT, and an empty list is no list at all
+impl PartialEq for Attributs {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().count() == other.iter().count()
+            && self.iter().all(|(key, value)| other.get_value(key) == Some(value))
+    }
 }
 
+impl Eq for Attributs {}
+
+// Values are taken as meant and quoted when written back if needed
 impl From<HashMap<String,String>> for Attributs {
     fn from(value: HashMap<String,String>) -> Self {
-        Attributs(Some(value))
+        value.into_iter().map(|(key, value)| Attribut { key, value: AttributValue::from(value) }).collect()
+    }
+}
+
+impl FromIterator<Attribut> for Attributs {
+    fn from_iter<T: IntoIterator<Item = Attribut>>(iter: T) -> Self {
+        let mut attributs = vec![];
+        iter.into_iter().for_each(|attribut| Attributs::insert(&mut attributs, attribut.key, attribut.value));
+        Attributs(Some(attributs).filter(|attributs| !attributs.is_empty()))
     }
 }
 
-impl ToString for Attributs {
-    fn to_string(&self) -> String {
-        self.0.clone().map(
-            |attributs| "[".to_string() + &attributs.iter().map(|(id, value)| id.clone()+"="+value).collect::<Vec<_>>().join(",") + "]")
-        .unwrap_or_default()
+impl Display for Attributs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(attributs) => write!(f, "[{}]", attributs.iter().map(|(id, value)| quote_id(id)+"="+&value.raw()).collect::<Vec<_>>().join(",")),
+            None => Ok(())
+        }
     }
 }
 
@@ -45,15 +119,119 @@ impl TryFrom<&String> for Attributs  {
     type Error = ParsingError;
 
     fn try_from(value: &String) -> Result<Self, Self::Error> {
-        value.split(",")
-            .map(as_key_value)
-            .collect::<Result<HashMap<String, String>,ParsingError>>()
-            .map(Attributs::from)
+        Parser::new(value)?.parse_attributs()
     }
 }
 
-fn as_key_value(value: &str) -> Result<(String, String), ParsingError> {
-    let splitted = value.trim().split_once("=")
-        .ok_or(ParsingError::DefaultError("Could not parse Attribute: ".to_string() + value))?;
-    Ok((splitted.0.to_string(),splitted.1.to_string()))
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_eq_default() {
+        let combinations :Vec<Attributs> = vec![
+            Attributs::try_from(&"".to_string()).unwrap(),
+            Attributs::from_iter(vec![]),
+            Attributs::from(HashMap::new()),
+            Attributs(Some(vec![])),
+            ];
+
+        combinations.into_iter().for_each(|combinaisons| assert_eq!(combinaisons, Attributs::default()));
+        assert_ne!(Attributs::try_from(&"color=red".to_string()).unwrap(), Attributs::default());
+    }
+
+    #[test]
+    fn try_from_ok() {
+        let attributs = Attributs::try_from(&"label=\"a, b=c\", color=red".to_string()).unwrap();
+        assert_eq!(attributs.label(), Some(&"a, b=c".to_string()));
+        assert_eq!(attributs.get_value("label").unwrap().raw(), "\"a, b=c\"");
+        assert_eq!(attributs.get("color"), Some(&"red".to_string()));
+    }
+
+    #[test]
+    fn try_from_typed_values() {
+        let attributs = Attributs::try_from(&"width=1.5, height=\"2\", fixedsize=true, label=<b>, color=red".to_string()).unwrap();
+        let kinds = ["width", "height", "fixedsize", "label", "color"].map(|key| attributs.get_value(key).map(std::mem::discriminant));
+        assert_eq!(kinds, [
+            Some(std::mem::discriminant(&AttributValue::Numeral(String::new()))),
+            Some(std::mem::discriminant(&AttributValue::Quoted { raw: String::new(), value: String::new() })),
+            Some(std::mem::discriminant(&AttributValue::Id(String::new()))),
+            Some(std::mem::discriminant(&AttributValue::Html(String::new()))),
+            Some(std::mem::discriminant(&AttributValue::Id(String::new()))),
+            ]);
+        assert_eq!(attributs.get_value("width").and_then(AttributValue::as_f64), Some(1.5));
+        assert_eq!(attributs.get_value("height").and_then(AttributValue::as_f64), Some(2.0));
+        assert_eq!(attributs.get_value("fixedsize").and_then(AttributValue::as_bool), Some(true));
+        assert_eq!(attributs.get_str("color"), Some("red"));
+        assert_eq!(attributs.get_str("missing"), None);
+    }
+
+    #[test]
+    fn try_from_concatenated_label() {
+        let attributs = Attributs::try_from(&"label=\"first line\\n\" +\n  \"second \\\"line\\\"\"".to_string()).unwrap();
+        assert_eq!(attributs.label(), Some(&"first line\\nsecond \"line\"".to_string()));
+        assert_eq!(attributs.get_value("label").unwrap().raw(), ("\"first line\\n\" +\n  \"second \\\"line\\\"\""));
+    }
+
+    #[test]
+    fn try_from_html_label() {
+        let attributs = Attributs::try_from(&"label=<<table><tr><td port=\"a\">x, y=[1]</td></tr></table>>, shape=plain".to_string()).unwrap();
+        let label = attributs.get_value("label").unwrap();
+        assert!(label.is_html());
+        assert_eq!(label.value(), "<table><tr><td port=\"a\">x, y=[1]</td></tr></table>");
+        assert_eq!(attributs.get("shape"), Some(&"plain".to_string()));
+        assert!(!attributs.get_value("shape").unwrap().is_html());
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut attributs = Attributs::try_from(&"shape=box, label=A, color=red, label=B".to_string()).unwrap();
+        assert_eq!(attributs.to_string(), "[shape=box,label=B,color=red]");
+
+        attributs.extend(&Attributs::try_from(&"width=2, shape=circle".to_string()).unwrap());
+        assert_eq!(attributs.to_string(), "[shape=circle,label=B,color=red,width=2]");
+
+        attributs.sort();
+        assert_eq!(attributs.to_string(), "[color=red,label=B,shape=circle,width=2]");
+    }
+
+    #[test]
+    fn set_and_remove() {
+        let mut attributs = Attributs::default();
+        attributs.set("label", "to B");
+        attributs.set("color", "red");
+        attributs.set("label", AttributValue::Html("<b>B</b>".to_string()));
+        assert_eq!(attributs.to_string(), "[label=<<b>B</b>>,color=red]");
+
+        assert_eq!(attributs.remove("label"), Some(AttributValue::Html("<b>B</b>".to_string())));
+        assert_eq!(attributs.remove("label"), None);
+        assert_eq!(attributs.to_string(), "[color=red]");
+    }
+
+    #[test]
+    fn equality_ignores_order() {
+        let first = Attributs::try_from(&"shape=box, label=\"A\"".to_string()).unwrap();
+        assert_eq!(first, Attributs::try_from(&"label=A; shape=box".to_string()).unwrap());
+        assert_ne!(first, Attributs::try_from(&"label=A".to_string()).unwrap());
+        assert_ne!(first, Attributs::try_from(&"label=A, shape=box, color=red".to_string()).unwrap());
+        assert_ne!(first, Attributs::default());
+    }
+
+    #[test]
+    fn display_quotes_values() {
+        let attributs = Attributs::from(HashMap::from([("label".to_string(), "to B".to_string())]));
+        assert_eq!(attributs.to_string(), "[label=\"to B\"]");
+    }
+
+    #[test]
+    fn try_from_semicolon_separators() {
+        let attributs = Attributs::try_from(&"label=a; color=red, shape=box;".to_string()).unwrap();
+        assert_eq!(attributs.attributs().map(|attributs| attributs.len()), Some(3));
+    }
+
+    #[test]
+    fn try_from_ko() {
+        assert!(Attributs::try_from(&"label".to_string()).is_err());
+        assert!(Attributs::try_from(&"label=a=b".to_string()).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+use thiserror::Error;
+
+use super::{attribut::Attribut, attribut_value::AttributValue, attributs::Attributs, dot_graph::DotGraph, edge::Edge, graph_type::GraphType, node::Node, type_relation::TypeRelation};
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum BuildError {
+    #[error("Empty identifier for {element}")]
+    EmptyIdentifier { element: String },
+    #[error("Edge '{edge}' does not use the operator of a {family}")]
+    MismatchedEdgeOperator { edge: String, family: GraphType },
+    #[error("An edge chain needs at least two nodes, found {count}")]
+    ShortChain { count: usize },
+}
+
+// Build a graph statement by statement, checking what the parser would reject
+pub struct DotGraphBuilder {
+    strict: bool,
+    family: GraphType,
+    graph: DotGraph,
+    errors: Vec<BuildError>,
+}
+
+impl DotGraphBuilder {
+    pub fn new(family: GraphType) -> Self {
+        DotGraphBuilder::with_name(family, None)
+    }
+
+    fn with_name(family: GraphType, name: Option<String>) -> Self {
+        DotGraphBuilder {
+            strict: false,
+            family,
+            graph: DotGraph::new(family, vec![], vec![], vec![], Attributs::default(), name),
+            errors: vec![],
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.check_identifier(name, "the graph name");
+        self.graph.set_name(Some(name.to_string()));
+        self
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn attribut(mut self, key: &str, value: impl Into<AttributValue>) -> Self {
+        self.check_identifier(key, "a graph attribute");
+        let attributs = Attributs::from_iter([Attribut { key: key.to_string(), value: value.into() }]);
+        self.graph.push_graph_attributs(attributs);
+        self
+    }
+
+    // node [ ... ] statement
+    pub fn node_defaults<'a, V: Into<AttributValue>>(mut self, attributs: impl IntoIterator<Item = (&'a str, V)>) -> Self {
+        let attributs = self.attributs(attributs);
+        self.graph.push_node_defaults(attributs);
+        self
+    }
+
+    // edge [ ... ] statement
+    pub fn edge_defaults<'a, V: Into<AttributValue>>(mut self, attributs: impl IntoIterator<Item = (&'a str, V)>) -> Self {
+        let attributs = self.attributs(attributs);
+        self.graph.push_edge_defaults(attributs);
+        self
+    }
+
+    pub fn node(self, identifier: &str) -> Self {
+        self.node_with(identifier, Vec::<(&str, &str)>::new())
+    }
+
+    pub fn node_with<'a, V: Into<AttributValue>>(mut self, identifier: &str, attributs: impl IntoIterator<Item = (&'a str, V)>) -> Self {
+        self.check_identifier(identifier, "a node");
+        let attributs = self.attributs(attributs);
+        self.graph.push_node(Node::new(identifier, attributs));
+        self
+    }
+
+    pub fn edge(self, node_out: &str, node_in: &str) -> Self {
+        self.edge_with(node_out, node_in, Vec::<(&str, &str)>::new())
+    }
+
+    pub fn edge_with<'a, V: Into<AttributValue>>(mut self, node_out: &str, node_in: &str, attributs: impl IntoIterator<Item = (&'a str, V)>) -> Self {
+        self.check_identifier(node_out, "an edge");
+        self.check_identifier(node_in, "an edge");
+        let attributs = self.attributs(attributs);
+        self.graph.push_edge(Edge { node_out: node_out.into(), node_in: node_in.into(), relation: TypeRelation::from(self.family), attributs });
+        self
+    }
+
+    // A -> B -> C, one edge per link of the chain
+    pub fn chain(self, identifiers: &[&str]) -> Self {
+        self.chain_with(identifiers, Vec::<(&str, &str)>::new())
+    }
+
+    // A -> B -> C [ ... ], every link of the chain sharing the attributes
+    pub fn chain_with<'a, V: Into<AttributValue>>(mut self, identifiers: &[&str], attributs: impl IntoIterator<Item = (&'a str, V)>) -> Self {
+        if identifiers.len() < 2 {
+            self.errors.push(BuildError::ShortChain { count: identifiers.len() });
+            return self;
+        }
+        let attributs = self.attributs(attributs);
+        identifiers.windows(2).fold(self, |builder, link| builder.edge_with(link[0], link[1], attributs.iter().map(|(key, value)| (key.as_str(), value.clone()))))
+    }
+
+    // An edge built elsewhere, its operator must match the graph type
+    pub fn add_edge(mut self, edge: Edge) -> Self {
+        if edge.relation != TypeRelation::from(self.family) {
+            self.errors.push(BuildError::MismatchedEdgeOperator { edge: edge.to_string(), family: self.family });
+        }
+        self.check_identifier(&edge.node_out.identifier, "an edge");
+        self.check_identifier(&edge.node_in.identifier, "an edge");
+        self.graph.push_edge(edge);
+        self
+    }
+
+    pub fn subgraph(mut self, name: &str, build: impl FnOnce(DotGraphBuilder) -> DotGraphBuilder) -> Self {
+        self.check_identifier(name, "a subgraph name");
+        self.push_subgraph(Some(name.to_string()), build)
+    }
+
+    pub fn anonymous_subgraph(self, build: impl FnOnce(DotGraphBuilder) -> DotGraphBuilder) -> Self {
+        self.push_subgraph(None, build)
+    }
+
+    fn push_subgraph(mut self, name: Option<String>, build: impl FnOnce(DotGraphBuilder) -> DotGraphBuilder) -> Self {
+        let sous_graphe = build(DotGraphBuilder::with_name(self.family, name));
+        self.errors.extend(sous_graphe.errors);
+        self.graph.push_sous_graphe(sous_graphe.graph);
+        self
+    }
+
+    // First invariant broken, if any
+    pub fn build(self) -> Result<DotGraph, BuildError> {
+        if let Some(error) = self.errors.into_iter().next() {
+            return Err(error);
+        }
+        let mut graph = self.graph;
+        graph.set_strict(self.strict);
+        Ok(graph)
+    }
+
+    fn attributs<'a, V: Into<AttributValue>>(&mut self, attributs: impl IntoIterator<Item = (&'a str, V)>) -> Attributs {
+        let mut result = Attributs::default();
+        attributs.into_iter().for_each(|(key, value)| {
+            self.check_identifier(key, "an attribute");
+            result.set(key, value);
+        });
+        result
+    }
+
+    fn check_identifier(&mut self, identifier: &str, element: &str) {
+        if identifier.is_empty() {
+            self.errors.push(BuildError::EmptyIdentifier { element: element.to_string() });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_ok() {
+        let graph = DotGraphBuilder::new(GraphType::Digraph)
+            .attribut("rankdir", "LR")
+            .name("Test")
+            .node_defaults([("shape", "box")])
+            .node("A")
+            .node_with("B", [("label", "node B")])
+            .chain(&["A", "B", "C"])
+            .subgraph("cluster_a", |sous_graphe| sous_graphe
+                .attribut("label", "A")
+                .edge_defaults([("color", "red")])
+                .edge_with("C", "D", [("weight", "2")])
+                .anonymous_subgraph(|anonymous| anonymous.attribut("rank", "same").node("D").node("E")))
+            .build()
+            .unwrap();
+
+        let parsed = DotGraph::try_from("digraph Test {rankdir=LR; node [shape=box]; A; B [label=\"node B\"]; A -> B -> C; subgraph cluster_a {label=A; edge [color=red]; C -> D [weight=2]; {rank=same; D; E}}}").unwrap();
+        assert_eq!(graph, parsed);
+        assert_eq!(DotGraph::try_from(graph.as_dot_content().as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn build_chain_with_attributs() {
+        let graph = DotGraphBuilder::new(GraphType::Digraph)
+            .name("Test")
+            .chain_with(&["A", "B", "C"], [("color", "red"), ("label", "a link")])
+            .build()
+            .unwrap();
+
+        let parsed = DotGraph::try_from("digraph Test {A -> B -> C [color=red, label=\"a link\"]}").unwrap();
+        assert_eq!(graph, parsed);
+        assert_eq!(graph.edges().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["A -> B [color=red,label=\"a link\"];", "B -> C [color=red,label=\"a link\"];"]);
+    }
+
+    #[test]
+    fn build_strict_merges_duplicate_edges() {
+        let graph = DotGraphBuilder::new(GraphType::Graph)
+            .strict(true)
+            .edge_with("A", "B", [("color", "red")])
+            .edge_with("B", "A", [("label", "x")])
+            .build()
+            .unwrap();
+
+        assert!(graph.is_strict());
+        assert_eq!(graph.edges().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["A -- B [color=red,label=x];"]);
+    }
+
+    #[test]
+    fn build_ko() {
+        let combinations :Vec<(DotGraphBuilder, BuildError)> = vec![
+            (DotGraphBuilder::new(GraphType::Digraph).node(""), BuildError::EmptyIdentifier { element: "a node".to_string() }),
+            (DotGraphBuilder::new(GraphType::Digraph).name(""), BuildError::EmptyIdentifier { element: "the graph name".to_string() }),
+            (DotGraphBuilder::new(GraphType::Digraph).node_with("A", [("", "x")]), BuildError::EmptyIdentifier { element: "an attribute".to_string() }),
+            (DotGraphBuilder::new(GraphType::Digraph).subgraph("s", |sous_graphe| sous_graphe.edge("A", "")), BuildError::EmptyIdentifier { element: "an edge".to_string() }),
+            (DotGraphBuilder::new(GraphType::Digraph).chain(&["A"]), BuildError::ShortChain { count: 1 }),
+            (DotGraphBuilder::new(GraphType::Digraph).chain_with(&["A", "B"], [("", "x")]), BuildError::EmptyIdentifier { element: "an attribute".to_string() }),
+            (DotGraphBuilder::new(GraphType::Digraph).add_edge(Edge::try_from(("A--B", "--")).unwrap()),
+                BuildError::MismatchedEdgeOperator { edge: "A -- B ;".to_string(), family: GraphType::Digraph }),
+            ];
+
+        combinations.into_iter().for_each(|combinaisons| assert_eq!(combinaisons.0.build().unwrap_err(), combinaisons.1));
+    }
+}
@@ -0,0 +1,210 @@
+use super::{parsing_error::ParsingError, span::Span};
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+impl ParsingError {
+    // Render the error with the failing source line and a caret, rustc style
+    pub fn render(&self, source: &str) -> String {
+        Renderer { colored: false }.render(self, source)
+    }
+
+    // Same as render, with ANSI colours for terminals
+    pub fn render_colored(&self, source: &str) -> String {
+        Renderer { colored: true }.render(self, source)
+    }
+
+    // Short explanation shown under the caret
+    fn label(&self) -> String {
+        match self {
+            ParsingError::UnexpectedCharacter { .. } => "this character is not valid in DOT".to_string(),
+            ParsingError::UnterminatedString { .. } => "this string is never closed".to_string(),
+            ParsingError::UnterminatedHtmlString { .. } => "this HTML string is never closed".to_string(),
+            ParsingError::UnterminatedComment { .. } => "this comment is never closed".to_string(),
+            ParsingError::InvalidNumeral { .. } => "this numeral has no digit".to_string(),
+            ParsingError::UnbalancedBrace { token, .. } if token == "{" => "this brace is never closed".to_string(),
+            ParsingError::UnbalancedBrace { .. } => "this brace closes nothing".to_string(),
+            ParsingError::UnknownEdgeOperator { .. } => "not an edge operator".to_string(),
+            ParsingError::MismatchedEdgeOperator { family, .. } => format!("not allowed in a {}", family),
+            ParsingError::MissingGraphKeyword { .. } => "expected 'graph' or 'digraph' here".to_string(),
+            ParsingError::UnexpectedToken { expected, .. } => format!("expected {}", expected),
+            ParsingError::UnexpectedEnd { expected, .. } => format!("expected {}", expected),
+            ParsingError::InFile { error, .. } => error.label(),
+            ParsingError::DefaultError(_) | ParsingError::Other(_) => String::default(),
+        }
+    }
+
+    // Hint on how to fix the content, if any
+    fn help(&self) -> Option<&str> {
+        match self {
+            ParsingError::UnterminatedString { .. } => Some("add the closing '\"', or escape inner quotes as '\\\"'"),
+            ParsingError::UnterminatedHtmlString { .. } => Some("check that every '<' has a matching '>'"),
+            ParsingError::UnterminatedComment { .. } => Some("add the closing '*/'"),
+            ParsingError::InvalidNumeral { .. } => Some("write at least one digit, as in '0.5' or '-.5', or quote the value"),
+            ParsingError::UnbalancedBrace { token, .. } if token == "{" => Some("add the missing '}'"),
+            ParsingError::UnbalancedBrace { .. } => Some("remove the extra '}'"),
+            ParsingError::UnknownEdgeOperator { .. } => Some("edges are written 'A -> B' in a digraph and 'A -- B' in a graph"),
+            ParsingError::MismatchedEdgeOperator { family, .. } if family == "digraph" => Some("use '->' in a digraph"),
+            ParsingError::MismatchedEdgeOperator { .. } => Some("use '--' in a graph"),
+            ParsingError::MissingGraphKeyword { .. } => Some("a DOT file starts with 'graph' or 'digraph', optionally preceded by 'strict'"),
+            ParsingError::UnexpectedCharacter { .. } => Some("quote the identifier if it contains special characters"),
+            ParsingError::InFile { error, .. } => error.help(),
+            _ => None
+        }
+    }
+
+    // Message without the position, which is shown by the snippet
+    fn headline(&self) -> String {
+        match self {
+            ParsingError::UnexpectedCharacter { token, .. } => format!("unexpected character '{}'", token),
+            ParsingError::UnterminatedString { .. } => "unterminated string".to_string(),
+            ParsingError::UnterminatedHtmlString { .. } => "unterminated HTML string".to_string(),
+            ParsingError::UnterminatedComment { .. } => "unterminated comment".to_string(),
+            ParsingError::InvalidNumeral { token, .. } => format!("invalid numeral '{}'", token),
+            ParsingError::UnbalancedBrace { token, .. } => format!("unbalanced brace '{}'", token),
+            ParsingError::UnknownEdgeOperator { token, .. } => format!("unknown edge operator '{}'", token),
+            ParsingError::MismatchedEdgeOperator { token, family, .. } => format!("edge operator '{}' in a {}", token, family),
+            ParsingError::MissingGraphKeyword { token, .. } => format!("missing graph keyword, found '{}'", token),
+            ParsingError::UnexpectedToken { token, expected, .. } => format!("expected {}, found '{}'", expected, token),
+            ParsingError::UnexpectedEnd { expected, .. } => format!("expected {}, found end of content", expected),
+            ParsingError::InFile { error, .. } => error.headline(),
+            ParsingError::DefaultError(_) | ParsingError::Other(_) => self.to_string(),
+        }
+    }
+}
+
+struct Renderer {
+    colored: bool
+}
+
+impl Renderer {
+    fn paint(&self, colour: &str, text: &str) -> String {
+        match self.colored {
+            true => format!("{}{}{}", colour, text, RESET),
+            false => text.to_string()
+        }
+    }
+
+    fn render(&self, error: &ParsingError, source: &str) -> String {
+        let mut content = self.paint(RED, "error") + &self.paint(BOLD, &(": ".to_string() + &error.headline())) + "\n";
+
+        // Line 0 is no position at all, as in a default span
+        let Some(span) = error.span().filter(|span| span.line > 0) else {
+            return content;
+        };
+
+        let line = source.lines().nth(span.line - 1).unwrap_or_default();
+        let line_number = span.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        content = content + &gutter + &self.paint(BLUE, "-->") + " " + error.path().unwrap_or("<input>") + ":" + &span.line.to_string() + ":" + &span.column.to_string() + "\n";
+        content = content + &gutter + " " + &self.paint(BLUE, "|") + "\n";
+        content = content + &self.paint(BLUE, &line_number) + " " + &self.paint(BLUE, "|") + " " + line + "\n";
+        content = content + &gutter + " " + &self.paint(BLUE, "|") + " " + &caret_padding(line, span) + &self.paint(RED, &("^".repeat(caret_width(line, span)) + " " + &error.label())) + "\n";
+
+        if let Some(help) = error.help() {
+            content = content + &gutter + " " + &self.paint(BLUE, "=") + " " + &self.paint(BOLD, "help") + ": " + help + "\n";
+        }
+        content
+    }
+}
+
+// Keep tabs so the caret lines up with the source line
+fn caret_padding(line: &str, span: &Span) -> String {
+    line.chars()
+        .chain(std::iter::repeat(' '))
+        .take(span.column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect()
+}
+
+// Underline the token, without going past the end of the line
+fn caret_width(line: &str, span: &Span) -> usize {
+    let mut bytes = 0;
+    line.chars()
+        .skip(span.column.saturating_sub(1))
+        .take_while(|c| { bytes += c.len_utf8(); bytes <= span.length })
+        .count()
+        .max(1)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::DotGraph;
+
+    use super::*;
+
+    #[test]
+    fn render_ok() {
+        let source = "digraph G {\n\tA -> B;\n\tB -- C;\n}";
+        let error = DotGraph::try_from(source).unwrap_err();
+
+        assert_eq!(error.render(source), [
+            "error: edge operator '--' in a digraph",
+            " --> <input>:3:4",
+            "  |",
+            "3 | \tB -- C;",
+            "  | \t  ^^ not allowed in a digraph",
+            "  = help: use '->' in a digraph",
+            ""].join("\n"));
+    }
+
+    #[test]
+    fn render_end_of_content() {
+        let source = "graph G {\n  A -- ";
+        let error = DotGraph::try_from(source).unwrap_err();
+
+        assert_eq!(error.render(source), [
+            "error: expected an identifier, found end of content",
+            " --> <input>:2:8",
+            "  |",
+            "2 |   A -- ",
+            "  |        ^ expected an identifier",
+            ""].join("\n"));
+    }
+
+    #[test]
+    fn render_without_position() {
+        let error = ParsingError::UnexpectedEnd { span: Span::default(), expected: "'}'".to_string() };
+        assert_eq!(error.render("abc"), "error: expected '}', found end of content\n");
+
+        let error = ParsingError::UnexpectedCharacter { span: Span { offset: 0, length: 1, line: 1, column: 0 }, token: "$".to_string() };
+        assert_eq!(error.render("$"), [
+            "error: unexpected character '$'",
+            " --> <input>:1:0",
+            "  |",
+            "1 | $",
+            "  | ^ this character is not valid in DOT",
+            "  = help: quote the identifier if it contains special characters",
+            ""].join("\n"));
+    }
+
+    #[test]
+    fn render_in_file() {
+        let source = "digraph G {\n  A [label=\"never closed];\n}";
+        let error = ParsingError::InFile { path: "graph.dot".to_string(), error: Box::new(DotGraph::try_from(source).unwrap_err()) };
+
+        let rendered = error.render(source);
+        assert!(rendered.starts_with("error: unterminated string\n --> graph.dot:2:12\n"));
+        assert!(rendered.contains("  |            ^ this string is never closed\n"));
+    }
+
+    #[test]
+    fn render_colored() {
+        let source = "digraph G { A }}";
+        let error = DotGraph::try_from(source).unwrap_err();
+
+        let rendered = error.render_colored(source);
+        assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m"));
+        assert!(rendered.contains("\x1b[1;31m^ this brace closes nothing\x1b[0m"));
+    }
+
+    #[test]
+    fn render_without_span() {
+        let error = ParsingError::DefaultError("boom".to_string());
+        assert_eq!(error.render(""), "error: An error occured: boom\n");
+    }
+}
@@ -1,33 +1,60 @@
-use std::{collections::HashMap, fs::{read_to_string, File}, io::Write};
+use std::{collections::{HashMap, HashSet}, fs::{read_to_string, File}, io::Write};
 
 use anyhow::Context;
-use log::{debug, info};
+use log::info;
 use crate::dot_parser::attributs::Attributs;
-use super::{attribut::Attribut, edge::Edge, graph_type::GraphType, node::Node, parsing_error::ParsingError};
+use super::{attribut_value::AttributValue, edge::Edge, endpoint::Endpoint, graph_type::GraphType, index::GraphIndex, lexer::quote_id, node::{GraphNode, Node}, parser::Parser, parsing_error::ParsingError, provenance::{EffectiveAttributs, Provenance}, schema::{self, Element, ValidationError}, type_relation::TypeRelation};
 
-#[derive(PartialEq,Clone, Eq)]
+#[derive(PartialEq,Clone, Eq, Debug)]
 #[cfg_attr(
     feature = "serde",
     derive(serde::Serialize, serde::Deserialize)
 )]
 pub struct DotGraph {
+    strict: bool,
     family: GraphType, 
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     sous_graphes: Vec<DotGraph>,
+    // Attributes of the graph itself, every graph [ ... ] and ID=ID statement merged
     attributs: Attributs,
-    name: String
+    name: Option<String>,
+    graph_attributs: Vec<Attributs>,
+    node_defaults: Vec<Attributs>,
+    edge_defaults: Vec<Attributs>,
+    // Order in which nodes, edges, subgraphs, graph attributes and defaults were declared
+    statements: Vec<Statement>
+}
+
+// Kind of each statement of a graph, the nth Node statement being the nth node and so on
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+enum Statement {
+    Node,
+    Edge,
+    SousGraphe,
+    GraphAttributs,
+    NodeDefaults,
+    EdgeDefaults
 }
 
 impl Default for DotGraph {
     fn default() -> Self {
         Self { 
+            strict: false,
             family: GraphType::Graph, 
             nodes: Default::default(), 
             edges: Default::default(), 
             sous_graphes: Default::default(), 
             attributs: Default::default(), 
-            name: Default::default() }
+            name: Default::default(),
+            graph_attributs: Default::default(),
+            node_defaults: Default::default(),
+            edge_defaults: Default::default(),
+            statements: Default::default() }
     }
 }
 
@@ -36,25 +63,36 @@ impl DotGraph {
         info!("Opening graph from: {}", path);
         let file = read_to_string(path)
             .with_context(|| format!("Reading file {}", path))?;
-        
-        let cleaned_file = file.lines()
-            .map(|line| line.trim_ascii())
-            .filter(|line| !line.is_empty() || line.starts_with("//"))
-            .collect::<Vec<&str>>()
-            .join("\r\n");
     
-        DotGraph::try_from(cleaned_file.as_str())
+        DotGraph::try_from(file.as_str())
+            .map_err(|error| ParsingError::InFile { path: path.to_string(), error: Box::new(error) })
+    }
+
+    // Parse a possibly invalid DOT content, keeping what could be read and every error found
+    pub fn try_from_recovering(content: &str) -> (DotGraph, Vec<ParsingError>) {
+        Parser::new_recovering(content).parse_graph_recovering()
     }
 
 
-    pub fn new(family: GraphType, nodes: Vec<Node>, edges: Vec<Edge>, sous_graphes: Vec<DotGraph>, attributs: Attributs, name: String) -> Self {
+    pub fn new(family: GraphType, nodes: Vec<Node>, edges: Vec<Edge>, sous_graphes: Vec<DotGraph>, attributs: Attributs, name: impl Into<Option<String>>) -> Self {
+        let graph_attributs: Vec<Attributs> = std::iter::once(attributs.clone()).filter(|attributs| attributs.iter().next().is_some()).collect();
+        let statements = std::iter::repeat_n(Statement::GraphAttributs, graph_attributs.len())
+            .chain(std::iter::repeat_n(Statement::Node, nodes.len()))
+            .chain(std::iter::repeat_n(Statement::Edge, edges.len()))
+            .chain(std::iter::repeat_n(Statement::SousGraphe, sous_graphes.len()))
+            .collect();
         DotGraph {
+            strict: false,
             family,
-            name,
+            name: name.into(),
             nodes,
             edges,
             attributs,
-            sous_graphes
+            sous_graphes,
+            graph_attributs,
+            node_defaults: vec![],
+            edge_defaults: vec![],
+            statements
         }
     }
     
@@ -65,244 +103,807 @@ impl DotGraph {
 impl TryFrom<&str> for DotGraph {
     type Error = ParsingError;
     fn try_from(content: &str) -> Result<Self, Self::Error> {
-        let mut cleaned_content = content.lines()
-            .map(clean_line)
-            .filter(|l| !l.is_empty() && !l.starts_with("//"))
-            .collect::<Vec<_>>().join(";")
-            .to_string();
-            
-        Self::create_graph(&mut cleaned_content, None)
+        Parser::new(content)?.parse_graph()
     }
 }
 
 
 impl DotGraph {
 
+    // Nodes of the graph then of its subgraphs, at any depth
     pub fn nodes(&self) ->Vec<Node> {
         let mut nodes = self.nodes.clone();  
         
         nodes
         .extend(
-            self.sous_graphes.iter().flat_map(|g| g.nodes.clone()));
+            self.sous_graphes.iter().flat_map(DotGraph::nodes));
 
         nodes
     }
 
+    // Edges of the graph then of its subgraphs, at any depth
     pub fn edges(&self) -> Vec<Edge> {
         let mut edges = self.edges.clone();  
-        edges.extend(self.sous_graphes.iter().flat_map(|g| g.edges.clone()));
+        edges.extend(self.sous_graphes.iter().flat_map(DotGraph::edges));
+        edges
+    }
+
+    // Edges at any depth, in the same order as edges(), without cloning them
+    pub(crate) fn edge_refs(&self) -> Vec<&Edge> {
+        let mut edges = self.edges.iter().collect::<Vec<_>>();
+        edges.extend(self.sous_graphes.iter().flat_map(DotGraph::edge_refs));
         edges
     }
 
-    fn create_graph(content: &mut String, parent: Option<GraphType>)  -> Result<DotGraph, ParsingError>{
-        debug!("creating graph from: {}", content);
-        if content.trim().is_empty() {
-            return Ok(DotGraph::default());
+    // Adjacency of the graph, to build once before many neighbourhood queries
+    pub fn index(&self) -> GraphIndex<'_> {
+        GraphIndex::new(self)
+    }
+
+    // Every node of the graph in order of appearance, with the ones only used by edges marked implicit
+    pub fn all_nodes(&self) -> Vec<GraphNode> {
+        let mut nodes = vec![];
+        self.collect_nodes(&mut vec![], &mut nodes, &mut HashMap::new());
+        nodes
+    }
+
+    fn collect_nodes(&self, path: &mut Vec<usize>, nodes: &mut Vec<GraphNode>, positions: &mut HashMap<String, usize>) {
+        let mut declared = self.nodes.iter();
+        let mut edges = self.edges.iter();
+        let mut sous_graphes = self.sous_graphes.iter().enumerate();
+        for statement in &self.statements {
+            match statement {
+                Statement::Node => if let Some(node) = declared.next() {
+                    let position = DotGraph::introduce_node(&node.identifier, path, nodes, positions);
+                    nodes[position].implicit = false;
+                    nodes[position].node.attributes.extend(&node.attributes);
+                },
+                Statement::Edge => if let Some(edge) = edges.next() {
+                    DotGraph::introduce_node(&edge.node_out.identifier, path, nodes, positions);
+                    DotGraph::introduce_node(&edge.node_in.identifier, path, nodes, positions);
+                },
+                Statement::SousGraphe => if let Some((position, sous_graphe)) = sous_graphes.next() {
+                    path.push(position);
+                    sous_graphe.collect_nodes(path, nodes, positions);
+                    path.pop();
+                },
+                _ => {}
+            }
         }
-    
-        let head_and_body = content.split_once("{").ok_or(ParsingError::DefaultError("Pas de corps ?".to_string()))?;
-        let head = head_and_body.0;
-        
-        let type_graph = get_type_graph(head, parent)?;
-        let name = head.split_once(" ").map(|(_gtype,name)| name).unwrap_or("NoName").trim();
-        
-        let mut body = head_and_body.1.to_string();
+    }
 
-        let sous_graphes = Self::extract_subgraphes(&mut body, type_graph)?; 
-        body.pop(); // Popping last } for the cleanest body 
+    // Position of the node, added as implicit where it is first seen
+    fn introduce_node(identifier: &str, path: &[usize], nodes: &mut Vec<GraphNode>, positions: &mut HashMap<String, usize>) -> usize {
+        *positions.entry(identifier.to_string()).or_insert_with(|| {
+            nodes.push(GraphNode { node: Node::new(identifier, Attributs::default()), implicit: true, sous_graphe: path.to_vec() });
+            nodes.len() - 1
+        })
+    }
 
-        let mut attributs = HashMap::default();
-        let mut nodes =vec![];
-        let mut edges =vec![];
-        let mut default_node_attribute = Attributs::default();
-        let mut default_edge_attribute = Attributs::default();
-        
-        body
-            .split(";")
-            .map(clean_line)
-            .filter(|l| !l.is_empty())
-            .try_for_each(|line| {
-                // Edge if it got the arrow
-                if line.contains(&type_graph.symbol()) {
-                    let edge = Edge::try_from((line, type_graph.symbol().as_str()))?;
-                    edges.push(edge);
-                    return Ok(());
-                } 
-    
-                if line.contains("[") || !line.contains("=") {
-                    let node = Node::try_from(&line.to_string())?;
-                    if node.identifier == "node" {
-                        default_node_attribute=node.attributes
-                    } else if node.identifier == "edge" {
-                        default_edge_attribute=node.attributes
-                    } else {
-                        nodes.push(node);   
+    // Subgraph at the given position in sous_graphes at each depth, the graph itself for an empty path
+    pub fn sous_graphe_at(&self, path: &[usize]) -> Option<&DotGraph> {
+        match path.split_first() {
+            Some((position, rest)) => self.sous_graphes.get(*position)?.sous_graphe_at(rest),
+            None => Some(self)
+        }
+    }
+
+    pub fn sous_graphes(&self) -> &Vec<DotGraph> {
+        &self.sous_graphes
+    }
+
+    // Subgraphs at any depth, parents before their children
+    pub fn all_sous_graphes(&self) -> Vec<&DotGraph> {
+        self.sous_graphes.iter()
+            .flat_map(|sous_graphe| std::iter::once(sous_graphe).chain(sous_graphe.all_sous_graphes()))
+            .collect()
+    }
+
+    // Subgraphs whose name starts with "cluster", at any depth
+    pub fn clusters(&self) -> Vec<&DotGraph> {
+        self.all_sous_graphes().into_iter().filter(|sous_graphe| sous_graphe.is_cluster()).collect()
+    }
+
+    // First subgraph with this name, at any depth
+    pub fn find_sous_graphe(&self, name: &str) -> Option<&DotGraph> {
+        self.sous_graphe_at(&self.path_to(name)?)
+    }
+
+    // Innermost subgraph where the node first appears, the graph itself for a node of the graph
+    pub fn sous_graphe_of(&self, identifier: &str) -> Option<&DotGraph> {
+        let node = self.all_nodes().into_iter().find(|node| node.node.identifier == identifier)?;
+        self.sous_graphe_at(&node.sous_graphe)
+    }
+
+    // Innermost cluster the node belongs to, wherever it first appears, as Graphviz draws it.
+    // A node of several sibling clusters is drawn in the first one
+    pub fn cluster_of(&self, identifier: &str) -> Option<&DotGraph> {
+        self.sous_graphes.iter()
+            .filter(|sous_graphe| sous_graphe.node_identifiers().iter().any(|node| node == identifier))
+            .find_map(|sous_graphe| sous_graphe.cluster_of(identifier).or(Some(sous_graphe).filter(|sous_graphe| sous_graphe.is_cluster())))
+    }
+
+    // Graphs containing the named subgraph, from its direct parent up to this graph
+    pub fn parents(&self, name: &str) -> Option<Vec<&DotGraph>> {
+        let path = self.path_to(name)?;
+        Some((0..path.len()).rev().filter_map(|depth| self.sous_graphe_at(&path[..depth])).collect())
+    }
+
+    // Move the declarations of the node into the named subgraph, or into the graph itself for None.
+    // Edges stay where they are declared: when one of them still introduces the node elsewhere, as A -> B inside
+    // another subgraph does, the node cannot move and the graph is left unchanged
+    pub fn move_node(&mut self, identifier: &str, destination: Option<&str>) -> bool {
+        let path = match destination {
+            Some(name) => self.path_to(name),
+            None => Some(vec![])
+        };
+        let Some(path) = path.filter(|_| self.node_identifiers().iter().any(|node| node == identifier)) else {
+            return false;
+        };
+
+        let mut moved = self.clone();
+        let mut attributes = Attributs::default();
+        moved.nodes_mut().into_iter()
+            .filter(|node| node.identifier == identifier)
+            .for_each(|node| attributes.extend(&node.attributes));
+        moved.retain_statements(&|node| node.identifier != identifier, &|_| true);
+        if let Some(sous_graphe) = moved.sous_graphe_at_mut(&path) {
+            sous_graphe.push_node(Node::new(identifier, attributes));
+        }
+
+        let arrived = moved.all_nodes().into_iter().any(|node| node.node.identifier == identifier && node.sous_graphe == path);
+        if arrived {
+            *self = moved;
+        }
+        arrived
+    }
+
+    fn path_to(&self, name: &str) -> Option<Vec<usize>> {
+        self.sous_graphes.iter().enumerate().find_map(|(position, sous_graphe)| match sous_graphe.name() == Some(name) {
+            true => Some(vec![position]),
+            false => sous_graphe.path_to(name).map(|path| [vec![position], path].concat())
+        })
+    }
+
+    // First subgraph with this name, at any depth, to change its attributes or content
+    pub fn find_sous_graphe_mut(&mut self, name: &str) -> Option<&mut DotGraph> {
+        let path = self.path_to(name)?;
+        self.sous_graphe_at_mut(&path)
+    }
+
+    fn sous_graphe_at_mut(&mut self, path: &[usize]) -> Option<&mut DotGraph> {
+        match path.split_first() {
+            Some((position, rest)) => self.sous_graphes.get_mut(*position)?.sous_graphe_at_mut(rest),
+            None => Some(self)
+        }
+    }
+
+    // Every node identifier of the graph and its subgraphs, declared or used by an edge, in order of appearance
+    pub(crate) fn node_identifiers(&self) -> Vec<String> {
+        let mut identifiers: Vec<String> = vec![];
+        let mut add = |identifier: &String| {
+            if !identifiers.contains(identifier) {
+                identifiers.push(identifier.clone());
+            }
+        };
+
+        self.nodes.iter().for_each(|node| add(&node.identifier));
+        self.edges.iter().for_each(|edge| {
+            add(&edge.node_out.identifier);
+            add(&edge.node_in.identifier);
+        });
+        self.sous_graphes.iter().flat_map(DotGraph::node_identifiers).for_each(|identifier| add(&identifier));
+        identifiers
+    }
+
+    // First declaration of the node, looking into subgraphs
+    pub fn find_node(&self, identifier: &str) -> Option<&Node> {
+        self.nodes.iter()
+            .find(|node| node.identifier == identifier)
+            .or_else(|| self.sous_graphes.iter().find_map(|sous_graphe| sous_graphe.find_node(identifier)))
+    }
+
+    // Node an edge endpoint refers to, whatever its port
+    pub fn resolve(&self, endpoint: &Endpoint) -> Option<&Node> {
+        self.find_node(&endpoint.identifier)
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    // A strict graph keeps a single edge between two nodes, duplicates are merged into the first one
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+        if strict {
+            self.merge_duplicate_edges();
+        }
+    }
+
+    fn merge_duplicate_edges(&mut self) {
+        let mut merged = HashMap::new();
+        let mut kept = HashSet::new();
+        let mut position = 0;
+        self.collect_duplicate_edges(&mut merged, &mut kept, &mut position);
+
+        let mut position = 0;
+        self.retain_merged_edges(&merged, &kept, &mut position);
+    }
+
+    // Visit edges in declaration order, remembering the first edge of each pair and the merged attributes
+    fn collect_duplicate_edges(&self, merged: &mut HashMap<(String, String), Attributs>, kept: &mut HashSet<usize>, position: &mut usize) {
+        let mut edges = self.edges.iter();
+        let mut sous_graphes = self.sous_graphes.iter();
+        self.statements.iter().for_each(|statement| match statement {
+            Statement::Edge => if let Some(edge) = edges.next() {
+                match merged.get_mut(&edge.pair()) {
+                    Some(attributs) => attributs.extend(&edge.attributs),
+                    None => {
+                        merged.insert(edge.pair(), edge.attributs.clone());
+                        kept.insert(*position);
                     }
-                    return Ok(());
                 }
-    
-                let att = Attribut::try_from(line)?;
-                attributs.insert(att.key, att.value);
-                Ok::<(), ParsingError>(())
-            })?;
-    
-            Ok(DotGraph {name: name.to_string(), family: type_graph, sous_graphes, nodes, edges, attributs: Attributs::from(attributs) })
+                *position += 1;
+            },
+            Statement::SousGraphe => if let Some(sous_graphe) = sous_graphes.next() {
+                sous_graphe.collect_duplicate_edges(merged, kept, position);
+            },
+            _ => {}
+        });
     }
-    
-    fn extract_subgraphes(body: &mut String, parent: GraphType) -> Result<Vec<DotGraph>, ParsingError> {
-        let mut sous_graphes_position = extract_subgraphes_position(body)?;
-    
-        let sous_graphes = sous_graphes_position
-                .iter()
-                .map(|(start,end)|Self::create_graph(&mut body[*start..*end+1].to_string(), Some(parent)))
-                .collect::<Result<Vec<DotGraph>, ParsingError>>()?;
-    
-        sous_graphes_position.reverse();
-        for i in sous_graphes_position {
-            body.replace_range(i.0..i.1+1, "");
+
+    // An edge merged away leaves its endpoints as node statements, unless the graph mentions them elsewhere,
+    // so that they stay members of its subgraph
+    fn retain_merged_edges(&mut self, merged: &HashMap<(String, String), Attributs>, kept: &HashSet<usize>, position: &mut usize) {
+        let mut edges = std::mem::take(&mut self.edges).into_iter();
+        let mut nodes = std::mem::take(&mut self.nodes).into_iter();
+        let mut sous_graphes = std::mem::take(&mut self.sous_graphes).into_iter();
+        let mut members = HashSet::new();
+        for statement in std::mem::take(&mut self.statements) {
+            match statement {
+                Statement::Edge => if let Some(mut edge) = edges.next() {
+                    let keep = kept.contains(position);
+                    *position += 1;
+                    match keep {
+                        true => {
+                            edge.attributs = merged.get(&edge.pair()).cloned().unwrap_or_default();
+                            self.push_edge(edge);
+                        },
+                        false => [edge.node_out.identifier, edge.node_in.identifier].into_iter().for_each(|identifier| {
+                            members.insert(self.nodes.len());
+                            self.push_node(Node::new(&identifier, Attributs::default()));
+                        })
+                    }
+                },
+                Statement::Node => if let Some(node) = nodes.next() {
+                    self.push_node(node);
+                },
+                Statement::SousGraphe => if let Some(mut sous_graphe) = sous_graphes.next() {
+                    sous_graphe.retain_merged_edges(merged, kept, position);
+                    self.push_sous_graphe(sous_graphe);
+                },
+                defaults => self.statements.push(defaults)
+            }
         }
+        self.drop_redundant_members(&members);
+    }
+
+    // Node statements left by merged edges are only needed for nodes the graph does not mention otherwise
+    fn drop_redundant_members(&mut self, members: &HashSet<usize>) {
+        let mut mentioned: HashSet<String> = self.nodes.iter().enumerate()
+            .filter(|(position, _)| !members.contains(position))
+            .map(|(_, node)| node.identifier.clone())
+            .chain(self.edges.iter().flat_map(|edge| [edge.node_out.identifier.clone(), edge.node_in.identifier.clone()]))
+            .chain(self.sous_graphes.iter().flat_map(DotGraph::node_identifiers))
+            .collect();
 
-        Ok(sous_graphes)
+        let mut nodes = std::mem::take(&mut self.nodes).into_iter().enumerate();
+        let mut retained = vec![];
+        self.statements.retain(|statement| match statement {
+            Statement::Node => match nodes.next() {
+                Some((position, node)) => {
+                    let keep = !members.contains(&position) || mentioned.insert(node.identifier.clone());
+                    if keep {
+                        retained.push(node);
+                    }
+                    keep
+                },
+                None => true
+            },
+            _ => true
+        });
+        self.nodes = retained;
     }
 
-    pub fn name(&self) -> &String {
-        &self.name
+    pub(crate) fn push_node(&mut self, node: Node) {
+        self.nodes.push(node);
+        self.statements.push(Statement::Node);
     }
 
-    pub fn write(&self, path: &str) -> Result<(), ParsingError> {
-        let content = self.as_dot_content();
-        let mut file = File::create(path).unwrap();
-        file.write_all(content.as_bytes()).unwrap();
-        Ok(())
+    pub(crate) fn push_edge(&mut self, edge: Edge) {
+        self.edges.push(edge);
+        self.statements.push(Statement::Edge);
     }
 
-    fn as_dot_content(&self) -> String {
-        let mut content = String::default();
+    pub(crate) fn push_sous_graphe(&mut self, sous_graphe: DotGraph) {
+        self.sous_graphes.push(sous_graphe);
+        self.statements.push(Statement::SousGraphe);
+    }
 
-        content = content + &self.family.to_string() + " " + &self.name + " { \r\n";
+    // node [ ... ] statement, applying to the nodes created after it in this graph and its subgraphs
+    pub(crate) fn push_node_defaults(&mut self, attributs: Attributs) {
+        self.node_defaults.push(attributs);
+        self.statements.push(Statement::NodeDefaults);
+    }
 
-        let nodes = self.nodes.iter().map(Node::to_string).fold(String::default(), |acc, node| acc +  "\r\n" + &node);   
-        let edges = self.edges.iter().map(Edge::to_string).fold(String::default(), |acc, edge| acc + "\r\n" + &edge);
+    // edge [ ... ] statement, applying to the edges created after it in this graph and its subgraphs
+    pub(crate) fn push_edge_defaults(&mut self, attributs: Attributs) {
+        self.edge_defaults.push(attributs);
+        self.statements.push(Statement::EdgeDefaults);
+    }
 
-        content = content + &nodes + "\r\n\r\n" + &edges;
+    // Attributes of the node once defaults are applied, a node takes the defaults in scope where it is first used
+    pub fn effective_node_attributs(&self, identifier: &str) -> Option<Attributs> {
+        self.resolution().nodes.into_iter()
+            .find(|(node, _)| node == identifier)
+            .map(|(_, effective)| effective.attributs())
+    }
 
-        let subgraphes_string = self.sous_graphes.iter().map(DotGraph::as_dot_content).fold(String::default(), |acc, sous_graph| acc + &sous_graph + "\r\n"); 
-        
+    // Every edge of the graph and its subgraphs in declaration order, with the defaults in scope applied
+    pub fn effective_edges(&self) -> Vec<Edge> {
+        self.resolution().edges.into_iter()
+            .map(|(edge, effective)| Edge { attributs: effective.attributs(), ..edge })
+            .collect()
+    }
 
-        content + "\r\n\r\n" + &subgraphes_string + "\r\n}"
+    // Effective attributes of the node, each saying whether it comes from a statement, a default or Graphviz
+    pub fn explain_node(&self, identifier: &str) -> Option<EffectiveAttributs> {
+        self.resolution().nodes.into_iter()
+            .find(|(node, _)| node == identifier)
+            .map(|(_, effective)| DotGraph::with_built_in(Element::Node, &effective))
+    }
+
+    // Effective attributes of every edge between the two nodes, either way for undirected edges, in declaration order
+    pub fn explain_edges(&self, node_out: &str, node_in: &str) -> Vec<EffectiveAttributs> {
+        let pair = self.pair_between(node_out, node_in);
+        self.resolution().edges.into_iter()
+            .filter(|(edge, _)| edge.pair() == pair)
+            .map(|(_, effective)| DotGraph::with_built_in(Element::Edge, &effective))
+            .collect()
+    }
+
+    fn with_built_in(element: Element, effective: &EffectiveAttributs) -> EffectiveAttributs {
+        let mut explained = EffectiveAttributs::built_in(element);
+        explained.merge(effective);
+        explained
     }
-}
 
+    fn resolution(&self) -> Resolution {
+        let mut resolution = Resolution::default();
+        self.resolve_defaults(EffectiveAttributs::default(), EffectiveAttributs::default(), &Provenance::GraphDefault, &mut resolution);
+        resolution
+    }
+
+    // Defaults declared here come from the given provenance, the ones of each subgraph from that subgraph
+    fn resolve_defaults(&self, mut node_defaults: EffectiveAttributs, mut edge_defaults: EffectiveAttributs, provenance: &Provenance, resolution: &mut Resolution) {
+        let mut nodes = self.nodes.iter();
+        let mut edges = self.edges.iter();
+        let mut sous_graphes = self.sous_graphes.iter();
+        let mut node_defaults_statements = self.node_defaults.iter();
+        let mut edge_defaults_statements = self.edge_defaults.iter();
 
-// Get the graph type from the first chars of content
-fn get_type_graph(content: &str, parent: Option<GraphType>) -> Result<GraphType, ParsingError> {
-    if content.starts_with("digraph") {
-        return Ok(GraphType::Digraph);
+        for statement in &self.statements {
+            match statement {
+                Statement::NodeDefaults => node_defaults.extend(node_defaults_statements.next().unwrap_or(&Attributs::default()), provenance),
+                Statement::EdgeDefaults => edge_defaults.extend(edge_defaults_statements.next().unwrap_or(&Attributs::default()), provenance),
+                Statement::Node => if let Some(node) = nodes.next() {
+                    resolution.node(&node.identifier, &node_defaults).extend(&node.attributes, &Provenance::Statement);
+                },
+                Statement::Edge => if let Some(edge) = edges.next() {
+                    resolution.node(&edge.node_out.identifier, &node_defaults);
+                    resolution.node(&edge.node_in.identifier, &node_defaults);
+                    let mut effective = edge_defaults.clone();
+                    effective.extend(&edge.attributs, &Provenance::Statement);
+                    resolution.edges.push((edge.clone(), effective));
+                },
+                Statement::SousGraphe => if let Some(sous_graphe) = sous_graphes.next() {
+                    let provenance = Provenance::SousGrapheDefault(sous_graphe.name.clone());
+                    sous_graphe.resolve_defaults(node_defaults.clone(), edge_defaults.clone(), &provenance, resolution);
+                },
+                Statement::GraphAttributs => {}
+            }
+        }
     }
 
-    if content.starts_with("graph") {
-        return Ok(GraphType::Graph);
+    // Declare the node, or add its attributes to the first declaration already there
+    pub fn add_node(&mut self, node: Node) {
+        match self.find_node_mut(&node.identifier) {
+            Some(declared) => declared.attributes.extend(&node.attributes),
+            None => self.push_node(node)
+        }
     }
 
-    if content.starts_with("subgraph") {
-        return parent.ok_or(ParsingError::DefaultError("Should have a parent".to_string()));
+    // Remove the node declarations and the edges using the node, at any depth
+    pub fn remove_node(&mut self, identifier: &str) -> bool {
+        let removed = self.retain_statements(&|node| node.identifier != identifier, &|edge| edge.node_out.identifier != identifier && edge.node_in.identifier != identifier);
+        removed > 0
     }
 
-    Err(ParsingError::DefaultError("No graph type detected".to_string()))
-} 
+    // Rename the node in its declarations and in every edge using it, at any depth
+    pub fn rename_node(&mut self, identifier: &str, new_identifier: &str) -> bool {
+        let mut renamed = false;
+        self.nodes_mut().into_iter()
+            .filter(|node| node.identifier == identifier)
+            .for_each(|node| {
+                node.identifier = new_identifier.to_string();
+                renamed = true;
+            });
+        self.edges_mut().into_iter()
+            .flat_map(|edge| [&mut edge.node_out, &mut edge.node_in])
+            .filter(|endpoint| endpoint.identifier == identifier)
+            .for_each(|endpoint| {
+                endpoint.identifier = new_identifier.to_string();
+                renamed = true;
+            });
 
-// Removing comments and trimming
-fn clean_line(line: &str) -> &str {
-    line.split_once("//").map(|a|a.0).unwrap_or(line).trim()
-}
+        if self.strict {
+            self.merge_duplicate_edges();
+        }
+        renamed
+    }
 
-fn extract_subgraphes_position(inside_block: &str) -> Result<Vec<(usize, usize)>, ParsingError> {
+    // The edge takes the operator of the graph, a strict graph merges it into the edge already linking the nodes
+    pub fn add_edge(&mut self, mut edge: Edge) {
+        edge.relation = TypeRelation::from(self.family);
+        let pair = edge.pair();
+        let strict = self.strict;
+        match self.edges_mut().into_iter().find(|existing| strict && existing.pair() == pair) {
+            Some(existing) => existing.attributs.extend(&edge.attributs),
+            None => self.push_edge(edge)
+        }
+    }
 
-    let mut remaining = inside_block.to_string();
+    // Remove the edges from one node to the other, in both directions for an undirected graph
+    pub fn remove_edges_between(&mut self, node_out: &str, node_in: &str) -> usize {
+        let pair = self.pair_between(node_out, node_in);
+        self.retain_statements(&|_| true, &|edge| edge.pair() != pair)
+    }
 
-    let mut sub_graphes_ranges = vec![];
-    let mut stack = 0;
-    while remaining.contains("subgraph"){
-        let start = remaining.find("subgraph").unwrap();
-        let end = next_block_range(&remaining)?.1;
-        sub_graphes_ranges.push((start+stack, end+stack));
-        stack =end+1;
+    // Set the attribute on the first declaration of the node, declaring it when it is only used by edges
+    pub fn set_node_attribut(&mut self, identifier: &str, key: &str, value: impl Into<AttributValue>) {
+        match self.find_node_mut(identifier) {
+            Some(node) => node.attributes.set(key, value),
+            None => {
+                let mut attributes = Attributs::default();
+                attributes.set(key, value);
+                self.push_node(Node::new(identifier, attributes));
+            }
+        }
+    }
 
-        remaining = remaining.split_at(end+1).1.to_string();
-    } 
-    Ok(sub_graphes_ranges)
-}
+    // Set the attribute on every edge from one node to the other, returning how many were changed
+    pub fn set_edge_attribut(&mut self, node_out: &str, node_in: &str, key: &str, value: impl Into<AttributValue>) -> usize {
+        let value = value.into();
+        let pair = self.pair_between(node_out, node_in);
+        let edges = self.edges_mut().into_iter().filter(|edge| edge.pair() == pair).collect::<Vec<_>>();
+        let count = edges.len();
+        edges.into_iter().for_each(|edge| edge.attributs.set(key, value.clone()));
+        count
+    }
 
-fn next_block_range(block: &str) -> Result<(usize, usize), ParsingError>{
-    let mut stack = 0;
-    let mut index = 0;
+    // Set a graph attribute in the leading graph [ ... ] statement, the later ones dropping the key,
+    // so that the subgraphs inherit it wherever they are declared
+    pub fn set_attribut(&mut self, key: &str, value: impl Into<AttributValue>) {
+        let value = value.into();
+        self.remove_attribut(key);
+        self.lead_with(Statement::GraphAttributs);
+        self.graph_attributs[0].set(key, value.clone());
+        self.attributs.set(key, value);
+    }
 
-    let mut range : (Option<usize>, Option<usize>)= (None, None);
-    let mut chars = block.chars();
-    let mut next= chars.next();
-    while next.is_some() {
-        let char = next.unwrap();
-        
-        if char == '{' {
-            stack+=1;
-            if range.0.is_none() {
-                range.0 = Some(index);
+    // Remove the attribute from every graph [ ... ] and ID=ID statement of this graph
+    pub fn remove_attribut(&mut self, key: &str) -> Option<AttributValue> {
+        self.graph_attributs.iter_mut().for_each(|attributs| {
+            attributs.remove(key);
+        });
+        self.attributs.remove(key)
+    }
+
+    // Node defaults of every node [ ... ] statement of this graph, later ones overriding earlier ones
+    pub fn node_defaults(&self) -> Attributs {
+        DotGraph::merged(&self.node_defaults)
+    }
+
+    // Edge defaults of every edge [ ... ] statement of this graph, later ones overriding earlier ones
+    pub fn edge_defaults(&self) -> Attributs {
+        DotGraph::merged(&self.edge_defaults)
+    }
+
+    // Set a node default for every node of this graph, in the leading node [ ... ] statement, the later ones dropping the key.
+    // Subgraphs setting it in their own node [ ... ] statements still override it
+    pub fn set_node_default(&mut self, key: &str, value: impl Into<AttributValue>) {
+        self.remove_node_default(key);
+        self.lead_with(Statement::NodeDefaults);
+        self.node_defaults[0].set(key, value);
+    }
+
+    // Set an edge default for every edge of this graph, in the leading edge [ ... ] statement, the later ones dropping the key.
+    // Subgraphs setting it in their own edge [ ... ] statements still override it
+    pub fn set_edge_default(&mut self, key: &str, value: impl Into<AttributValue>) {
+        self.remove_edge_default(key);
+        self.lead_with(Statement::EdgeDefaults);
+        self.edge_defaults[0].set(key, value);
+    }
+
+    // Remove the default from every node [ ... ] statement of this graph
+    pub fn remove_node_default(&mut self, key: &str) -> Option<AttributValue> {
+        self.node_defaults.iter_mut().filter_map(|defaults| defaults.remove(key)).last()
+    }
+
+    // Remove the default from every edge [ ... ] statement of this graph
+    pub fn remove_edge_default(&mut self, key: &str) -> Option<AttributValue> {
+        self.edge_defaults.iter_mut().filter_map(|defaults| defaults.remove(key)).last()
+    }
+
+    fn merged(defaults: &[Attributs]) -> Attributs {
+        defaults.iter().fold(Attributs::default(), |mut merged, attributs| {
+            merged.extend(attributs);
+            merged
+        })
+    }
+
+    // Make sure the graph starts with such a defaults statement, added before any other statement when missing
+    fn lead_with(&mut self, kind: Statement) {
+        let leading = self.statements.iter().take_while(|statement| matches!(statement, Statement::GraphAttributs | Statement::NodeDefaults | Statement::EdgeDefaults)).any(|statement| *statement == kind);
+        if !leading {
+            self.statements.insert(0, kind);
+            match kind {
+                Statement::GraphAttributs => self.graph_attributs.insert(0, Attributs::default()),
+                Statement::NodeDefaults => self.node_defaults.insert(0, Attributs::default()),
+                _ => self.edge_defaults.insert(0, Attributs::default())
             }
         }
+    }
 
-        if char == '}' {
-            stack -= 1;
-            if stack == 0 {
-                return match range.0 {
-                    Some(start) => Ok((start, index)),
-                    None => Err(ParsingError::DefaultError("Parsing exception error: no starting brackets".to_string()))
+    fn pair_between(&self, node_out: &str, node_in: &str) -> (String, String) {
+        Edge { node_out: node_out.into(), node_in: node_in.into(), relation: TypeRelation::from(self.family), attributs: Attributs::default() }.pair()
+    }
+
+    fn find_node_mut(&mut self, identifier: &str) -> Option<&mut Node> {
+        self.nodes_mut().into_iter().find(|node| node.identifier == identifier)
+    }
+
+    fn nodes_mut(&mut self) -> Vec<&mut Node> {
+        let mut nodes = self.nodes.iter_mut().collect::<Vec<_>>();
+        nodes.extend(self.sous_graphes.iter_mut().flat_map(DotGraph::nodes_mut));
+        nodes
+    }
+
+    fn edges_mut(&mut self) -> Vec<&mut Edge> {
+        let mut edges = self.edges.iter_mut().collect::<Vec<_>>();
+        edges.extend(self.sous_graphes.iter_mut().flat_map(DotGraph::edges_mut));
+        edges
+    }
+
+    // Drop the nodes and edges not kept, at any depth, along with their statements; returns how many were dropped
+    fn retain_statements(&mut self, keep_node: &dyn Fn(&Node) -> bool, keep_edge: &dyn Fn(&Edge) -> bool) -> usize {
+        let mut removed = 0;
+        let mut nodes = std::mem::take(&mut self.nodes).into_iter();
+        let mut edges = std::mem::take(&mut self.edges).into_iter();
+        let mut sous_graphes = self.sous_graphes.iter_mut();
+        self.statements.retain(|statement| match statement {
+            Statement::Node => match nodes.next() {
+                Some(node) if keep_node(&node) => {
+                    self.nodes.push(node);
+                    true
+                },
+                _ => {
+                    removed += 1;
+                    false
+                }
+            },
+            Statement::Edge => match edges.next() {
+                Some(edge) if keep_edge(&edge) => {
+                    self.edges.push(edge);
+                    true
+                },
+                _ => {
+                    removed += 1;
+                    false
+                }
+            },
+            Statement::SousGraphe => {
+                if let Some(sous_graphe) = sous_graphes.next() {
+                    removed += sous_graphe.retain_statements(keep_node, keep_edge);
                 }
+                true
+            },
+            _ => true
+        });
+        removed
+    }
+
+    // graph [ ... ] or ID=ID statement, applying to what is declared after it. Following statements of the kind
+    // have the same scope, so they are merged
+    pub(crate) fn push_graph_attributs(&mut self, attributs: Attributs) {
+        self.attributs.extend(&attributs);
+        match (self.statements.last(), self.graph_attributs.last_mut()) {
+            (Some(Statement::GraphAttributs), Some(last)) => last.extend(&attributs),
+            _ => {
+                self.graph_attributs.push(attributs);
+                self.statements.push(Statement::GraphAttributs);
             }
-            if stack < 0 {
-                return Err(ParsingError::DefaultError("Too many }".to_string()));
+        }
+    }
+
+    pub fn attributs(&self) -> &Attributs {
+        &self.attributs
+    }
+
+    // None for anonymous graphs and subgraphs
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn is_cluster(&self) -> bool {
+        self.name.as_ref().is_some_and(|name| name.starts_with("cluster"))
+    }
+
+    pub(crate) fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    // Typed values of common graph attributes, falling back to the Graphviz defaults
+    pub fn rankdir(&self) -> Option<&str> {
+        schema::text(&self.attributs, "rankdir", Element::Graph)
+    }
+
+    pub fn bgcolor(&self) -> Option<&str> {
+        schema::text(&self.attributs, "bgcolor", Element::Graph)
+    }
+
+    pub fn fontsize(&self) -> Option<f64> {
+        schema::number(&self.attributs, "fontsize", Element::Graph)
+    }
+
+    pub fn nodesep(&self) -> Option<f64> {
+        schema::number(&self.attributs, "nodesep", Element::Graph)
+    }
+
+    pub fn compound(&self) -> Option<bool> {
+        schema::boolean(&self.attributs, "compound", Element::Graph)
+    }
+
+    pub fn splines(&self) -> Option<&str> {
+        schema::text(&self.attributs, "splines", Element::Graph)
+    }
+
+    // Unknown attributes, wrong values and attributes set on the wrong element, at any depth
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = vec![];
+        self.validate_as(Element::Graph, &self.description("graph"), &mut errors);
+        errors
+    }
+
+    fn validate_as(&self, element: Element, description: &str, errors: &mut Vec<ValidationError>) {
+        errors.extend(schema::validate(&self.attributs, element, description));
+        self.node_defaults.iter().for_each(|defaults| errors.extend(schema::validate(defaults, Element::Node, &format!("node defaults of {}", description))));
+        self.edge_defaults.iter().for_each(|defaults| errors.extend(schema::validate(defaults, Element::Edge, &format!("edge defaults of {}", description))));
+        self.nodes.iter().for_each(|node| errors.extend(node.validate()));
+        self.edges.iter().for_each(|edge| errors.extend(edge.validate()));
+        self.sous_graphes.iter().for_each(|sous_graphe| {
+            match sous_graphe.is_cluster() {
+                true => sous_graphe.validate_as(Element::Cluster, &sous_graphe.description("cluster"), errors),
+                false => sous_graphe.validate_as(Element::Subgraph, &sous_graphe.description("subgraph"), errors)
             }
-        } 
-        index +=1;
-        next = chars.next();
+        });
     }
 
-    Err(ParsingError::DefaultError("Missing ending }".to_string()))
-}
+    fn description(&self, kind: &str) -> String {
+        match &self.name {
+            Some(name) => format!("{} {}", kind, name),
+            None => format!("anonymous {}", kind)
+        }
+    }
 
+    // Sort every attribute list by key, at any depth, for a canonical output
+    pub fn sort_attributs(&mut self) {
+        self.attributs.sort();
+        self.graph_attributs.iter_mut().for_each(Attributs::sort);
+        self.nodes.iter_mut().for_each(|node| node.attributes.sort());
+        self.edges.iter_mut().for_each(|edge| edge.attributs.sort());
+        self.node_defaults.iter_mut().chain(self.edge_defaults.iter_mut()).for_each(Attributs::sort);
+        self.sous_graphes.iter_mut().for_each(DotGraph::sort_attributs);
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::{collections::HashMap, vec};
+    pub fn write(&self, path: &str) -> Result<(), ParsingError> {
+        let content = self.as_dot_content();
+        let mut file = File::create(path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        Ok(())
+    }
 
-    use super::*;
+    pub(crate) fn as_dot_content(&self) -> String {
+        let head = self.head(self.family.to_string());
+        match self.strict {
+            true => self.content("strict ".to_string() + &head),
+            false => self.content(head)
+        }
+    }
 
-    
+    fn subgraph_content(&self) -> String {
+        self.content(self.head("subgraph".to_string()))
+    }
 
-    #[test]
-    fn test_find_ending_pos_combinations() {
-        let combinations :Vec<(&str, (usize,usize))> = vec![
-            ("{test -> a;}", (0,11)),
-            ("{{}}", (0,3)),
-            ("{icitoutvabien}", (0,14)),
-            ("{{{{}}}}", (0,7)),
-            ("{{{{}}}}}", (0,7)),
-            ("graph Test {A;subgraph{D;}A->C}", (11, 30))
-            ];
-            
-        combinations.iter().for_each(|combinaisons| assert_eq!(next_block_range(combinaisons.0).unwrap(), combinaisons.1));
+    // Anonymous graphs are written without name, so that anonymous subgraphs stay distinct
+    fn head(&self, keyword: String) -> String {
+        match &self.name {
+            Some(name) => keyword + " " + &quote_id(name),
+            None => keyword
+        }
     }
 
-    #[test]
-    fn test_find_ending_pos_combinations_ko() {
-        let combinations :Vec<(&str, &str)> = vec![
-            ("}test{}", "Too many }"),
-            ("{testt", "Missing ending }"),
-            ("{test{}", "Missing ending }")
-            ];
-            
-        combinations.iter().for_each(|combinaisons| assert_eq!(next_block_range(combinaisons.0).unwrap_err().to_string(), ParsingError::DefaultError(combinaisons.1.to_string()).to_string()));
+    fn content(&self, head: String) -> String {
+        let mut content = String::default();
 
-    } 
+        content = content + &head + " { \r\n";
+
+        let mut graph_attributs = self.graph_attributs.iter();
+        let mut nodes = self.nodes.iter();
+        let mut edges = self.edges.iter();
+        let mut sous_graphes = self.sous_graphes.iter();
+        let mut node_defaults = self.node_defaults.iter();
+        let mut edge_defaults = self.edge_defaults.iter();
+
+        let statements = self.statements.iter()
+            .filter_map(|statement| match statement {
+                Statement::Node => nodes.next().map(Node::to_string),
+                Statement::Edge => edges.next().map(Edge::to_string),
+                Statement::SousGraphe => sous_graphes.next().map(DotGraph::subgraph_content),
+                Statement::GraphAttributs => graph_attributs.next().map(|attributs| "graph ".to_string() + &attribute_list(attributs) + ";"),
+                Statement::NodeDefaults => node_defaults.next().map(|attributs| "node ".to_string() + &attribute_list(attributs) + ";"),
+                Statement::EdgeDefaults => edge_defaults.next().map(|attributs| "edge ".to_string() + &attribute_list(attributs) + ";"),
+            })
+            .fold(String::default(), |acc, statement| acc + "\r\n" + &statement);
+
+        content + &statements + "\r\n}"
+    }
+}
+
+// Attribute statements need brackets even when empty
+fn attribute_list(attributs: &Attributs) -> String {
+    match attributs.to_string().is_empty() {
+        true => "[]".to_string(),
+        false => attributs.to_string()
+    }
+}
+
+// Effective attributes of every node, in creation order, and of every edge
+#[derive(Default)]
+struct Resolution {
+    nodes: Vec<(String, EffectiveAttributs)>,
+    positions: HashMap<String, usize>,
+    edges: Vec<(Edge, EffectiveAttributs)>
+}
+
+impl Resolution {
+    // Attributes of the node, created with the given defaults when first seen
+    fn node(&mut self, identifier: &str, defaults: &EffectiveAttributs) -> &mut EffectiveAttributs {
+        let position = *self.positions.entry(identifier.to_string()).or_insert_with(|| {
+            self.nodes.push((identifier.to_string(), defaults.clone()));
+            self.nodes.len() - 1
+        });
+        &mut self.nodes[position].1
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use crate::dot_parser::attribut_value::AttributValue;
+    use super::*;
 
 
     #[test]
@@ -313,7 +914,7 @@ mod tests {
         let mut map_attribut = HashMap::new();
         map_attribut.insert("label".to_string(), "test".to_string());
         map_attribut.insert("encore".to_string(), "toto".to_string());
-        assert_eq!(result.name, "Test".to_string());
+        assert_eq!(result.name(), Some("Test"));
         assert_eq!(result.nodes, 
             vec![
                 Node::new("A",Attributs::default()),
@@ -321,7 +922,7 @@ mod tests {
         assert_eq!(result.edges, 
             vec![
                 Edge::try_from(("A->B", "->")).unwrap(),
-                Edge::try_from(("B->A[label=\"to B\"", "->")).unwrap(),
+                Edge::try_from(("B->A[label=\"to B\"]", "->")).unwrap(),
                 Edge::try_from(("A->C", "->")).unwrap()]);
         assert_eq!(result.sous_graphes.len(), 2);
     }
@@ -334,7 +935,7 @@ mod tests {
         let mut map_attribut = HashMap::new();
         map_attribut.insert("encore".to_string(), "toto".to_string());
         map_attribut.insert("label".to_string(), "test".to_string());
-        assert_eq!(result.name, "Test".to_string());
+        assert_eq!(result.name(), Some("Test"));
         assert_eq!(result.nodes, 
             vec![
                 Node::new("A",Attributs::default()),
@@ -342,51 +943,562 @@ mod tests {
         assert_eq!(result.edges, 
             vec![
                 Edge::try_from(("A->B", "->")).unwrap(),
-                Edge::try_from(("B->A[label=\"to B\"", "->")).unwrap(),
+                Edge::try_from(("B->A[label=\"to B\"]", "->")).unwrap(),
                 Edge::try_from(("A->C", "->")).unwrap()]);
         assert_eq!(result.sous_graphes.len(), 2);
     }
 
+    #[test]
+    fn graph_try_from_keeps_quoted_delimiters() {
+        let input = "digraph Test {A [label=\"a; b -> c {x}\"]; A -> B [label=\"x=1, y=[2]\"]}";
+
+        let result = DotGraph::try_from(input).unwrap();
+        assert_eq!(result.nodes[0].attributes.label(), Some(&"a; b -> c {x}".to_string()));
+        assert_eq!(result.edges[0].attributs.label(), Some(&"x=1, y=[2]".to_string()));
+    }
 
     #[test]
-    fn extract_subgraphes_position_ok() {
-        let combinations :Vec<(&str,Vec<(usize, usize)>)> = vec![
-            ("subgraph{tetsautres}",vec![(0,19)]),
-            ("another what ?subgraph{tetsautres}", vec![(14,33)]),
-            ("subgraph{} subgraph{}",vec![(0,9), (11,20)]),
-            ("subgraph{E;} subgraph{H;}",vec![(0,11), (13,24)]),
-            ("encore un test subgraph{tetsautres} et au subgraph{ } voila du boulout", vec![(15,34),(42,52)]),
-            ("subgraph{C;D;C->D;}\r\n", vec![(0,18)]),
-            ("no sub grhaph", vec![])
+    fn graph_try_from_ko() {
+        let combinations :Vec<(&str, &str)> = vec![
+            ("digraph Test {A;subgraph{D;}", "Unbalanced brace '{' at line 1, column 14"),
+            ("digraph Test {A;}}", "Unbalanced brace '}' at line 1, column 18"),
+            ("Test {A;}", "Missing graph keyword, found 'Test' at line 1, column 1"),
             ];
+            
+        combinations.iter().for_each(|combinaisons| assert_eq!(DotGraph::try_from(combinaisons.0).unwrap_err().to_string(), combinaisons.1));
+    }
 
-        combinations.iter()
-            .for_each(|combinaisons| 
-                {
-                    let result = extract_subgraphes_position(combinaisons.0).unwrap();
-                    assert_eq!(result, combinaisons.1);
-                }
-            );
-    } 
+    #[test]
+    fn graph_from_file_ko_reports_path_and_position() {
+        let path = std::env::temp_dir().join("ilmen_dot_parser_graph_from_file_ko.dot");
+        std::fs::write(&path, "digraph Test {\n  A -> B;\n  B -> \"C;\n}").unwrap();
+        let path = path.to_str().unwrap();
+
+        let error = DotGraph::graph_from_file(path).unwrap_err();
+        assert_eq!(error.path(), Some(path));
+        assert_eq!(error.span().map(|span| (span.line, span.column)), Some((3, 8)));
+        assert_eq!(error.to_string(), format!("{}:3:8: Unterminated string starting at line 3, column 8", path));
+    }
 
     #[test]
-    fn extract_subgraphes_ok() {
-        let combinations :Vec<(&str,usize, &str)> = vec![
-            ("subgraph{A->B}", 1, ""),
-            ("another what ?subgraph{C->D}", 1, "another what ?"),
-            ("subgraph{E;} subgraph{H;}",2, " "),
-            ("encore un test subgraph {G->D;} et au subgraph {A;} voila du boulout", 2, "encore un test  et au  voila du boulout"),
-            ("subgraph{X;Y;Z->E;}\r\n", 1, "\r\n" ),
-            ];
+    fn graph_try_from_recovering() {
+        let input = "digraph Test {\nA -> ;\nB [label=\"ok\"]\nC -- D\nE\n}";
 
-        combinations.iter()
-            .for_each(|combinaisons| 
-                {
-                    let mut content = combinaisons.0.to_string(); 
-                    let result = DotGraph::extract_subgraphes(&mut content, GraphType::Digraph).unwrap();
-                    assert_eq!(result.len(), combinaisons.1);
-                    assert_eq!(content, combinaisons.2)
-                }
-            );
-    } 
-}
\ No newline at end of file
+        let (graph, errors) = DotGraph::try_from_recovering(input);
+        assert_eq!(errors.iter().map(|error| error.span().unwrap().line).collect::<Vec<_>>(), vec![2, 4]);
+        assert_eq!(graph.nodes, vec![Node::try_from(&"B[label=\"ok\"]".to_string()).unwrap(), Node::new("E", Attributs::default())]);
+    }
+
+    #[test]
+    fn resolve_endpoints() {
+        let graph = DotGraph::try_from("digraph Test {A [shape=record]; A:out:ne -> B:in; subgraph s {B [color=red]}}").unwrap();
+        let edge = &graph.edges[0];
+
+        assert_eq!(graph.resolve(&edge.node_out).map(|node| node.identifier.as_str()), Some("A"));
+        assert_eq!(graph.resolve(&edge.node_in).and_then(|node| node.attributes.get("color")), Some(&"red".to_string()));
+        assert_eq!(graph.resolve(&Endpoint::from("C")), None);
+    }
+
+    #[test]
+    fn write_then_read_ports() {
+        let graph = DotGraph::try_from("digraph Test {A:out:ne -> B:in; B:s -> A}").unwrap();
+        let content = graph.as_dot_content();
+
+        assert_eq!(DotGraph::try_from(content.as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn strict_graph_merges_duplicate_edges() {
+        let graph = DotGraph::try_from("strict digraph Test {A -> B [color=red]; B -> A; A -> B [label=x, color=blue]; subgraph s {A -> B [style=bold]}}").unwrap();
+
+        assert!(graph.is_strict());
+        assert_eq!(graph.edges().len(), 2);
+        assert_eq!(graph.edges[0].attributs.get("color"), Some(&"blue".to_string()));
+        assert_eq!(graph.edges[0].attributs.get("label"), Some(&"x".to_string()));
+        assert_eq!(graph.edges[0].attributs.get("style"), Some(&"bold".to_string()));
+        assert!(graph.sous_graphes[0].edges.is_empty());
+    }
+
+    #[test]
+    fn strict_undirected_graph_merges_both_directions() {
+        let graph = DotGraph::try_from("strict graph Test {A -- B; B -- A [color=red]; A:p -- B:q; A -- A}").unwrap();
+
+        assert_eq!(graph.edges().len(), 2);
+        assert_eq!(graph.edges[0].attributs.get("color"), Some(&"red".to_string()));
+    }
+
+    #[test]
+    fn not_strict_graph_keeps_duplicate_edges() {
+        let mut graph = DotGraph::try_from("digraph Test {A -> B; A -> B}").unwrap();
+        assert!(!graph.is_strict());
+        assert_eq!(graph.edges().len(), 2);
+
+        graph.set_strict(true);
+        assert_eq!(graph.edges().len(), 1);
+    }
+
+    #[test]
+    fn write_then_read_strict() {
+        let graph = DotGraph::try_from("strict digraph Test {A -> B}").unwrap();
+        let content = graph.as_dot_content();
+
+        assert!(content.starts_with("strict digraph Test {"));
+        assert_eq!(DotGraph::try_from(content.as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn write_then_read_quoted_names() {
+        let graph = DotGraph::try_from("digraph \"My \\\"Graph\\\"\" {subgraph \"cluster 1\" {A}}").unwrap();
+        let content = graph.as_dot_content();
+
+        assert!(content.starts_with("digraph \"My \\\"Graph\\\"\" {"));
+        assert_eq!(DotGraph::try_from(content.as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn defaults_apply_to_later_statements() {
+        let graph = DotGraph::try_from("digraph Test {A; node [color=red, shape=box]; B; A -> C; node [color=blue]; D [shape=circle]; edge [style=bold]; A -> D [color=green]}").unwrap();
+
+        assert_eq!(graph.effective_node_attributs("A"), Some(Attributs::default()));
+        assert_eq!(graph.effective_node_attributs("B"), Some(Attributs::try_from(&"color=red, shape=box".to_string()).unwrap()));
+        assert_eq!(graph.effective_node_attributs("C"), Some(Attributs::try_from(&"color=red, shape=box".to_string()).unwrap()));
+        assert_eq!(graph.effective_node_attributs("D"), Some(Attributs::try_from(&"color=blue, shape=circle".to_string()).unwrap()));
+        assert_eq!(graph.effective_node_attributs("E"), None);
+        assert_eq!(graph.effective_edges().iter().map(|edge| edge.attributs.clone()).collect::<Vec<_>>(),
+            vec![Attributs::default(), Attributs::try_from(&"style=bold, color=green".to_string()).unwrap()]);
+    }
+
+    #[test]
+    fn defaults_are_inherited_by_subgraphs() {
+        let graph = DotGraph::try_from("digraph Test {node [color=red]; edge [color=red]; subgraph s {node [shape=box]; edge [style=dashed]; A -> B; subgraph t {C}} node [color=blue]; subgraph u {D -> E} F}").unwrap();
+
+        assert_eq!(graph.effective_node_attributs("A"), Some(Attributs::try_from(&"color=red, shape=box".to_string()).unwrap()));
+        assert_eq!(graph.effective_node_attributs("C"), Some(Attributs::try_from(&"color=red, shape=box".to_string()).unwrap()));
+        assert_eq!(graph.effective_node_attributs("D"), Some(Attributs::try_from(&"color=blue".to_string()).unwrap()));
+        assert_eq!(graph.effective_node_attributs("F"), Some(Attributs::try_from(&"color=blue".to_string()).unwrap()));
+        assert_eq!(graph.effective_edges().iter().map(|edge| edge.attributs.clone()).collect::<Vec<_>>(),
+            vec![Attributs::try_from(&"color=red, style=dashed".to_string()).unwrap(), Attributs::try_from(&"color=red".to_string()).unwrap()]);
+    }
+
+    #[test]
+    fn node_keeps_defaults_from_its_creation() {
+        let graph = DotGraph::try_from("digraph Test {A -> B; subgraph s {node [color=red]; A [label=a]; G}}").unwrap();
+
+        assert_eq!(graph.effective_node_attributs("A"), Some(Attributs::try_from(&"label=a".to_string()).unwrap()));
+        assert_eq!(graph.effective_node_attributs("G"), Some(Attributs::try_from(&"color=red".to_string()).unwrap()));
+    }
+
+    #[test]
+    fn write_then_read_defaults() {
+        let graph = DotGraph::try_from("digraph Test {A; node [color=red]; B; edge []; subgraph s {edge [style=bold]; A -> B}; B -> A}").unwrap();
+        let content = graph.as_dot_content();
+
+        assert_eq!(DotGraph::try_from(content.as_str()).unwrap(), graph);
+        assert_eq!(graph.effective_node_attributs("B"), Some(Attributs::try_from(&"color=red".to_string()).unwrap()));
+    }
+
+    #[test]
+    fn write_then_read_graph_attributes() {
+        let graph = DotGraph::try_from("digraph Test {graph [rankdir=LR][splines=ortho]; label=\"a; b\"; A; subgraph cluster_a {color=blue; B}}").unwrap();
+        let content = graph.as_dot_content();
+
+        assert!(content.contains("graph [color=blue];"));
+        assert_eq!(DotGraph::try_from(content.as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn try_from_commented_graph() {
+        let graph = DotGraph::try_from("# 1 \"generated.gv\"\n/* generated\n   by a tool */\ndigraph Test { // nodes\nA [URL=\"http://x\"]; /* A -> C; */ A -> B // edge\n}").unwrap();
+
+        assert_eq!(graph.nodes().len(), 1);
+        assert_eq!(graph.nodes[0].attributes.get("URL"), Some(&"http://x".to_string()));
+        assert_eq!(graph.edges().iter().map(|edge| edge.to_string()).collect::<Vec<_>>(), vec!["A -> B ;"]);
+    }
+
+    #[test]
+    fn anonymous_and_nested_subgraphs() {
+        let graph = DotGraph::try_from("digraph Test {A; { rank=same; B; C } subgraph s1 { D; subgraph s2 { E -> F; { G; subgraph s3 { H -> I } } } } {rank=same; J}}").unwrap();
+
+        assert_eq!(graph.sous_graphes.len(), 3);
+        assert_eq!(graph.sous_graphes[0].attributs.get("rank"), Some(&"same".to_string()));
+        assert_eq!(graph.sous_graphes[1].sous_graphes[0].sous_graphes[0].sous_graphes[0].name(), Some("s3"));
+        assert_eq!(graph.nodes().iter().map(|node| node.identifier.as_str()).collect::<Vec<_>>(), vec!["A", "B", "C", "D", "G", "J"]);
+        assert_eq!(graph.edges().iter().map(|edge| edge.to_string()).collect::<Vec<_>>(), vec!["E -> F ;", "H -> I ;"]);
+    }
+
+    #[test]
+    fn write_then_read_anonymous_subgraphs() {
+        let graph = DotGraph::try_from("digraph Test {{rank=same; A; B} {rank=min; C} subgraph s {{D}}}").unwrap();
+        let content = graph.as_dot_content();
+
+        assert!(!content.contains("NoName"));
+        assert_eq!(DotGraph::try_from(content.as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn write_then_read_quoted_strings() {
+        let graph = DotGraph::try_from("digraph Test {\"node 1\" [label=\"first \" +\n \"second\", tooltip=\"say \\\"hi\\\"\"]; \"node 1\":\"port a\" -> B}").unwrap();
+        let read = DotGraph::try_from(graph.as_dot_content().as_str()).unwrap();
+
+        assert_eq!(read, graph);
+        assert_eq!(read.nodes()[0].identifier, "node 1");
+        assert_eq!(read.nodes()[0].attributes.label(), Some(&"first second".to_string()));
+        assert_eq!(read.nodes()[0].attributes.get("tooltip"), Some(&"say \"hi\"".to_string()));
+    }
+
+    #[test]
+    fn write_then_read_html_labels() {
+        let label = "<table border=\"0\"><tr><td>A &amp; B</td></tr>\n<tr><td port=\"out\">-&gt;</td></tr></table>";
+        let graph = DotGraph::try_from(format!("digraph Test {{A [shape=plain, label=<{}>]; B [label=\"<b>\"]; A:out -> B}}", label).as_str()).unwrap();
+        let content = graph.as_dot_content();
+        let read = DotGraph::try_from(content.as_str()).unwrap();
+
+        assert!(content.contains(&format!("label=<{}>", label)));
+        assert_eq!(read, graph);
+        assert!(read.nodes()[0].attributes.get_value("label").is_some_and(AttributValue::is_html));
+        assert!(!read.nodes()[1].attributes.get_value("label").is_some_and(AttributValue::is_html));
+    }
+
+    #[test]
+    fn write_graph_attributs_in_place() {
+        let input = "digraph G { subgraph cluster_a { A } color=blue; subgraph cluster_b { B } graph [label=x]; C }";
+        let graph = DotGraph::try_from(input).unwrap();
+        let content = graph.as_dot_content();
+
+        assert_eq!(graph.attributs().to_string(), "[color=blue,label=x]");
+        assert!(content.find("subgraph cluster_a").unwrap() < content.find("graph [color=blue];").unwrap());
+        assert!(content.find("graph [color=blue];").unwrap() < content.find("subgraph cluster_b").unwrap());
+        assert!(content.find("subgraph cluster_b").unwrap() < content.find("graph [label=x];").unwrap());
+        assert_eq!(DotGraph::try_from(content.as_str()).unwrap(), graph);
+
+        let mut graph = graph;
+        graph.set_attribut("color", "red");
+        let content = graph.as_dot_content();
+        assert!(content.find("graph [color=red];").unwrap() < content.find("subgraph cluster_a").unwrap());
+        assert!(!content.contains("color=blue"));
+        assert_eq!(graph.remove_attribut("label").map(|value| value.value().clone()), Some("x".to_string()));
+        assert_eq!(graph.attributs().to_string(), "[color=red]");
+        assert_eq!(DotGraph::try_from(graph.as_dot_content().as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn write_keeps_attributs_order() {
+        let input = "digraph Test {size=\"4,4\"; rankdir=LR; node [shape=box, color=red]; A [style=filled, label=A, fillcolor=blue]; A -> B [weight=2, label=x]; subgraph s {rank=same; B [width=1, height=2]}}";
+        let mut graph = DotGraph::try_from(input).unwrap();
+        let content = graph.as_dot_content();
+
+        assert_eq!(content, DotGraph::try_from(input).unwrap().as_dot_content());
+        ["graph [size=\"4,4\",rankdir=LR];", "node [shape=box,color=red];", "A[style=filled,label=A,fillcolor=blue];", "A -> B [weight=2,label=x];", "B[width=1,height=2];"]
+            .iter().for_each(|expected| assert!(content.contains(expected), "{} not in {}", expected, content));
+
+        graph.sort_attributs();
+        let sorted = graph.as_dot_content();
+        ["graph [rankdir=LR,size=\"4,4\"];", "node [color=red,shape=box];", "A[fillcolor=blue,label=A,style=filled];", "A -> B [label=x,weight=2];", "B[height=2,width=1];"]
+            .iter().for_each(|expected| assert!(sorted.contains(expected), "{} not in {}", expected, sorted));
+        assert_eq!(DotGraph::try_from(sorted.as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn typed_graph_getters() {
+        let graph = DotGraph::try_from("digraph Test {rankdir=LR; compound=true; A}").unwrap();
+        assert_eq!(graph.rankdir(), Some("LR"));
+        assert_eq!(graph.compound(), Some(true));
+        assert_eq!(graph.nodesep(), Some(0.25));
+        assert_eq!(graph.bgcolor(), None);
+    }
+
+    #[test]
+    fn validate_graph() {
+        let graph = DotGraph::try_from("digraph Test {rankdir=LR; rank=same; node [shape=box]; edge [arrowhead=nope]; A [colour=red]; A -> B [weight=heavy]; subgraph cluster_a {label=A; color=blue; rank=same} subgraph s {rank=same; bgcolor=red} {rank=sideways}}").unwrap();
+        assert_eq!(graph.validate().iter().map(ToString::to_string).collect::<Vec<_>>(), vec![
+            "Attribute 'rank' does not apply to graph Test",
+            "Attribute 'arrowhead' on edge defaults of graph Test expects an arrow type, found 'nope'",
+            "Unknown attribute 'colour' on node A",
+            "Attribute 'weight' on edge A -> B expects a number, found 'heavy'",
+            "Attribute 'bgcolor' does not apply to subgraph s",
+            "Attribute 'rank' on anonymous subgraph expects one of same, min, source, max, sink, found 'sideways'",
+            ]);
+    }
+
+    #[test]
+    fn add_nodes_and_edges() {
+        let mut graph = DotGraph::try_from("digraph Test {A; subgraph s {B [color=red]}}").unwrap();
+        graph.add_node(Node::try_from(&"B[shape=box]".to_string()).unwrap());
+        graph.add_node(Node::new("C", Attributs::default()));
+        graph.add_edge(Edge::try_from(("A--C[label=x]", "--")).unwrap());
+
+        assert_eq!(graph.nodes().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["A;", "C;", "B[color=red,shape=box];"]);
+        assert_eq!(graph.edges().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["A -> C [label=x];"]);
+        assert_eq!(DotGraph::try_from(graph.as_dot_content().as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn add_edge_to_strict_graph_merges() {
+        let mut graph = DotGraph::try_from("strict graph Test {A -- B [color=red]}").unwrap();
+        graph.add_edge(Edge::try_from(("B--A[label=x]", "--")).unwrap());
+        graph.add_edge(Edge::try_from(("B--C", "--")).unwrap());
+
+        assert_eq!(graph.edges().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["A -- B [color=red,label=x];", "B -- C ;"]);
+    }
+
+    #[test]
+    fn remove_node_and_incident_edges() {
+        let mut graph = DotGraph::try_from("digraph Test {A; B; A -> B; subgraph s {B -> C; C; {D -> B}} C -> D}").unwrap();
+
+        assert!(graph.remove_node("B"));
+        assert!(!graph.remove_node("B"));
+        assert_eq!(graph.nodes().iter().map(|node| node.identifier.as_str()).collect::<Vec<_>>(), vec!["A", "C"]);
+        assert_eq!(graph.edges().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["C -> D ;"]);
+        assert_eq!(DotGraph::try_from(graph.as_dot_content().as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn rename_node_everywhere() {
+        let mut graph = DotGraph::try_from("digraph Test {A [label=a]; A:p -> B; subgraph s {B -> A:n}}").unwrap();
+
+        assert!(graph.rename_node("A", "new A"));
+        assert!(!graph.rename_node("A", "Z"));
+        assert!(graph.find_node("new A").is_some());
+        assert_eq!(graph.edges().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["\"new A\":p -> B ;", "B -> \"new A\":n ;"]);
+        assert_eq!(DotGraph::try_from(graph.as_dot_content().as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn rename_node_in_strict_graph_merges() {
+        let mut graph = DotGraph::try_from("strict digraph Test {A -> C [color=red]; B -> C [label=x]}").unwrap();
+        graph.rename_node("B", "A");
+        assert_eq!(graph.edges().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["A -> C [color=red,label=x];"]);
+    }
+
+    #[test]
+    fn remove_edges_between_nodes() {
+        let mut graph = DotGraph::try_from("digraph Test {A -> B; B -> A; subgraph s {A -> B [label=x]}}").unwrap();
+        assert_eq!(graph.remove_edges_between("A", "B"), 2);
+        assert_eq!(graph.edges().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["B -> A ;"]);
+
+        let mut graph = DotGraph::try_from("graph Test {A -- B; B -- A; B -- C}").unwrap();
+        assert_eq!(graph.remove_edges_between("A", "B"), 2);
+        assert_eq!(graph.edges().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["B -- C ;"]);
+    }
+
+    #[test]
+    fn set_attributs() {
+        let mut graph = DotGraph::try_from("digraph Test {A -> B; subgraph s {B [color=red]; A -> B}}").unwrap();
+        graph.set_node_attribut("B", "color", "blue");
+        graph.set_node_attribut("A", "label", "node A");
+        assert_eq!(graph.set_edge_attribut("A", "B", "weight", "2"), 2);
+        assert_eq!(graph.set_edge_attribut("B", "A", "weight", "2"), 0);
+        graph.set_attribut("rankdir", "LR");
+
+        assert_eq!(graph.find_node("B").unwrap().color(), Some("blue"));
+        assert_eq!(graph.find_node("A").unwrap().attributes.label(), Some(&"node A".to_string()));
+        assert!(graph.edges().iter().all(|edge| edge.weight() == Some(2.0)));
+        assert_eq!(graph.rankdir(), Some("LR"));
+        assert_eq!(DotGraph::try_from(graph.as_dot_content().as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn all_nodes_with_implicit_ones() {
+        let graph = DotGraph::try_from("digraph Test {A -> B; subgraph s1 {C; B [color=red]; {D -> E; C -> F}} subgraph s2 {E; A -> G}}").unwrap();
+        let nodes = graph.all_nodes();
+
+        assert_eq!(nodes.iter().map(|node| (node.node.identifier.as_str(), node.implicit, node.sous_graphe.clone())).collect::<Vec<_>>(), vec![
+            ("A", true, vec![]),
+            ("B", false, vec![]),
+            ("C", false, vec![0]),
+            ("D", true, vec![0, 0]),
+            ("E", false, vec![0, 0]),
+            ("F", true, vec![0, 0]),
+            ("G", true, vec![1]),
+            ]);
+        assert_eq!(nodes[1].node.attributes.get("color"), Some(&"red".to_string()));
+        assert_eq!(graph.sous_graphe_at(&nodes[3].sous_graphe).map(|sous_graphe| sous_graphe.nodes().len()), Some(0));
+        assert_eq!(graph.sous_graphe_at(&nodes[6].sous_graphe).and_then(DotGraph::name), Some("s2"));
+        assert!(graph.sous_graphe_at(&[2]).is_none());
+    }
+
+    #[test]
+    fn hierarchy_queries() {
+        let graph = DotGraph::try_from("digraph Test {A; subgraph cluster_eu {B; subgraph paris {C; {D}} subgraph cluster_lyon {E}} subgraph us {F -> G}}").unwrap();
+        fn names(graphs: Vec<&DotGraph>) -> Vec<Option<&str>> {
+            graphs.into_iter().map(DotGraph::name).collect()
+        }
+
+        assert_eq!(names(graph.all_sous_graphes()), vec![Some("cluster_eu"), Some("paris"), None, Some("cluster_lyon"), Some("us")]);
+        assert_eq!(names(graph.clusters()), vec![Some("cluster_eu"), Some("cluster_lyon")]);
+        assert_eq!(graph.find_sous_graphe("cluster_lyon").map(|cluster| cluster.nodes().len()), Some(1));
+        assert!(graph.find_sous_graphe("asia").is_none());
+
+        assert_eq!(graph.sous_graphe_of("D").map(DotGraph::name), Some(None));
+        assert_eq!(graph.sous_graphe_of("A").and_then(DotGraph::name), Some("Test"));
+        assert_eq!(graph.sous_graphe_of("G").and_then(DotGraph::name), Some("us"));
+        assert_eq!(graph.cluster_of("D").and_then(DotGraph::name), Some("cluster_eu"));
+        assert_eq!(graph.cluster_of("E").and_then(DotGraph::name), Some("cluster_lyon"));
+        assert!(graph.cluster_of("F").is_none());
+
+        let membership = DotGraph::try_from("digraph Test {A -> B; subgraph s {B} subgraph cluster_x {A -> B; subgraph cluster_y {B}} subgraph cluster_z {B}}").unwrap();
+        assert_eq!(membership.sous_graphe_of("A").and_then(DotGraph::name), Some("Test"));
+        assert_eq!(membership.cluster_of("A").and_then(DotGraph::name), Some("cluster_x"));
+        assert_eq!(membership.cluster_of("B").and_then(DotGraph::name), Some("cluster_y"));
+
+        assert_eq!(graph.parents("cluster_lyon").map(names), Some(vec![Some("cluster_eu"), Some("Test")]));
+        assert_eq!(graph.parents("us").map(names), Some(vec![Some("Test")]));
+        assert!(graph.parents("asia").is_none());
+    }
+
+    #[test]
+    fn strict_merge_keeps_cluster_members() {
+        let graph = DotGraph::try_from("strict digraph G { A -> B; subgraph cluster_x { A -> B [color=red] } subgraph cluster_y { C -> D; A -> C; C -> D } }").unwrap();
+
+        assert_eq!(graph.edges().iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["A -> B [color=red];", "C -> D ;", "A -> C ;"]);
+        assert_eq!(graph.find_sous_graphe("cluster_x").map(|cluster| cluster.node_identifiers()), Some(vec!["A".to_string(), "B".to_string()]));
+        assert_eq!(graph.find_sous_graphe("cluster_y").map(|cluster| cluster.nodes().len()), Some(0));
+        assert_eq!(graph.cluster_of("A").and_then(DotGraph::name), Some("cluster_x"));
+        assert_eq!(graph.cluster_of("B").and_then(DotGraph::name), Some("cluster_x"));
+        assert_eq!(graph.cluster_of("D").and_then(DotGraph::name), Some("cluster_y"));
+        assert_eq!(DotGraph::try_from(graph.as_dot_content().as_str()).unwrap(), graph);
+
+        let graph = DotGraph::try_from("strict digraph G { A -> B; subgraph cluster_x { A -> B } }").unwrap();
+        assert_eq!(graph.cluster_of("A").and_then(DotGraph::name), Some("cluster_x"));
+        assert_eq!(graph.find_sous_graphe("cluster_x").map(|cluster| cluster.node_identifiers()), Some(vec!["A".to_string(), "B".to_string()]));
+    }
+
+    #[test]
+    fn move_node_between_sous_graphes() {
+        let mut graph = DotGraph::try_from("digraph Test {A [color=red]; subgraph cluster_a {B; A [label=a]} subgraph cluster_b {C} A -> C}").unwrap();
+
+        assert!(graph.move_node("A", Some("cluster_b")));
+        assert_eq!(graph.cluster_of("A").and_then(DotGraph::name), Some("cluster_b"));
+        assert_eq!(graph.find_node("A").map(ToString::to_string), Some("A[color=red,label=a];".to_string()));
+        assert_eq!(graph.edges().len(), 1);
+
+        assert!(graph.move_node("B", None));
+        assert!(graph.cluster_of("B").is_none());
+        assert!(!graph.move_node("A", Some("cluster_c")));
+        assert!(!graph.move_node("Z", None));
+        assert_eq!(DotGraph::try_from(graph.as_dot_content().as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn move_node_introduced_by_edge() {
+        let input = "digraph G { subgraph cluster_a { A -> B } subgraph cluster_b { C } }";
+        let mut graph = DotGraph::try_from(input).unwrap();
+
+        assert!(!graph.move_node("A", Some("cluster_b")));
+        assert_eq!(graph, DotGraph::try_from(input).unwrap());
+        assert_eq!(graph.cluster_of("A").and_then(DotGraph::name), Some("cluster_a"));
+
+        assert!(graph.move_node("C", Some("cluster_a")));
+        assert_eq!(graph.cluster_of("C").and_then(DotGraph::name), Some("cluster_a"));
+    }
+
+    #[test]
+    fn explain_attributs() {
+        let graph = DotGraph::try_from("digraph Test {node [color=red]; edge [color=green]; A [shape=box]; subgraph cluster_a {node [shape=circle]; {node [color=blue]; B} C} A -> B [color=grey]; B -> A; A -> C}").unwrap();
+
+        let combinations = vec![
+            ("A", "color", "red", Provenance::GraphDefault),
+            ("A", "shape", "box", Provenance::Statement),
+            ("A", "fontsize", "14", Provenance::BuiltIn),
+            ("B", "color", "blue", Provenance::SousGrapheDefault(None)),
+            ("B", "shape", "circle", Provenance::SousGrapheDefault(Some("cluster_a".to_string()))),
+            ("C", "color", "red", Provenance::GraphDefault),
+            ("C", "shape", "circle", Provenance::SousGrapheDefault(Some("cluster_a".to_string()))),
+        ];
+
+        combinations.into_iter().for_each(|combinaisons| {
+            let explained = graph.explain_node(combinaisons.0).unwrap();
+            let effective = explained.get(combinaisons.1).unwrap();
+            assert_eq!((effective.value.value().as_str(), &effective.provenance), (combinaisons.2, &combinaisons.3), "{} {}", combinaisons.0, combinaisons.1);
+        });
+        assert_eq!(graph.explain_node("Z"), None);
+
+        let edges = graph.explain_edges("A", "B");
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].value("color").map(|value| value.value().as_str()), Some("grey"));
+        assert_eq!(edges[0].provenance("color"), Some(&Provenance::Statement));
+        assert_eq!(edges[0].provenance("arrowhead"), Some(&Provenance::BuiltIn));
+        assert_eq!(graph.explain_edges("B", "A")[0].provenance("color"), Some(&Provenance::GraphDefault));
+        assert!(graph.explain_edges("C", "A").is_empty());
+        assert_eq!(graph.effective_node_attributs("B").map(|attributs| attributs.to_string()), Some("[color=blue,shape=circle]".to_string()));
+    }
+
+    #[test]
+    fn set_default_over_later_override() {
+        let mut graph = DotGraph::try_from("digraph G { A; node [color=blue, shape=box]; B; edge [color=blue]; A -> B; subgraph s {node [color=green]; C} }").unwrap();
+        graph.set_node_default("color", "red");
+        graph.set_edge_default("color", "red");
+
+        assert_eq!(graph.node_defaults().to_string(), "[color=red,shape=box]");
+        assert_eq!(graph.edge_defaults().to_string(), "[color=red]");
+        assert_eq!(graph.effective_node_attributs("A").map(|attributs| attributs.to_string()), Some("[color=red]".to_string()));
+        assert_eq!(graph.effective_node_attributs("B").map(|attributs| attributs.to_string()), Some("[color=red,shape=box]".to_string()));
+        assert_eq!(graph.effective_node_attributs("C").map(|attributs| attributs.to_string()), Some("[color=green,shape=box]".to_string()));
+        assert_eq!(graph.effective_edges()[0].attributs.to_string(), "[color=red]");
+        assert_eq!(DotGraph::try_from(graph.as_dot_content().as_str()).unwrap(), graph);
+    }
+
+    #[test]
+    fn graph_and_sous_graphe_attributs() {
+        let mut graph = DotGraph::try_from("digraph Test {rankdir=LR; subgraph cluster_a {label=A; B}}").unwrap();
+        graph.set_attribut("splines", "ortho");
+        assert_eq!(graph.remove_attribut("rankdir").map(|value| value.value().clone()), Some("LR".to_string()));
+        let cluster = graph.find_sous_graphe_mut("cluster_a").unwrap();
+        cluster.set_attribut("label", "zone A");
+        cluster.set_attribut("color", "blue");
+
+        let read = DotGraph::try_from(graph.as_dot_content().as_str()).unwrap();
+        assert_eq!(read.attributs().to_string(), "[splines=ortho]");
+        assert_eq!(read.find_sous_graphe("cluster_a").map(|cluster| cluster.attributs().to_string()), Some("[label=\"zone A\",color=blue]".to_string()));
+    }
+
+    #[test]
+    fn node_and_edge_defaults() {
+        let mut graph = DotGraph::try_from("digraph Test {A; node [shape=box]; B; edge [color=red]; A -> B; node [color=blue]; subgraph s {node [shape=circle]; C}}").unwrap();
+        assert_eq!(graph.node_defaults().to_string(), "[shape=box,color=blue]");
+        assert_eq!(graph.edge_defaults().to_string(), "[color=red]");
+        assert_eq!(graph.find_sous_graphe("s").map(|sous_graphe| sous_graphe.node_defaults().to_string()), Some("[shape=circle]".to_string()));
+
+        graph.set_node_default("fontsize", "10");
+        graph.set_edge_default("arrowhead", "vee");
+        graph.set_node_default("fontname", "Arial");
+        assert_eq!(graph.remove_node_default("color").map(|value| value.value().clone()), Some("blue".to_string()));
+        assert_eq!(graph.remove_edge_default("style"), None);
+
+        let read = DotGraph::try_from(graph.as_dot_content().as_str()).unwrap();
+        assert_eq!(read, graph);
+        assert_eq!(read.effective_node_attributs("A").map(|attributs| attributs.to_string()), Some("[fontsize=10,fontname=Arial]".to_string()));
+        assert_eq!(read.effective_node_attributs("C").map(|attributs| attributs.to_string()), Some("[fontsize=10,fontname=Arial,shape=circle]".to_string()));
+        assert_eq!(read.effective_edges()[0].attributs.to_string(), "[arrowhead=vee,color=red]");
+    }
+
+    #[test]
+    fn write_then_read_backslashes() {
+        let mut graph = DotGraph::try_from("digraph Test {A; \"x\\\\y\" -> A [label=\"a\\lb\\\\\"]}").unwrap();
+        graph.set_node_attribut("A", "label", "C:\\\\");
+        graph.set_node_attribut("A", "tooltip", "say \\\\\"hi\" \\n");
+        graph.add_node(Node::new("dir\\sub\\\\", Attributs::default()));
+        graph.add_edge(Edge { node_out: "dir\\sub\\\\".into(), node_in: "A".into(), relation: TypeRelation::Oriente, attributs: Attributs::default() });
+
+        assert_eq!(graph.find_node("A").and_then(|node| node.attributes.get("label").cloned()), Some("C:\\\\".to_string()));
+        let read = DotGraph::try_from(graph.as_dot_content().as_str()).unwrap();
+        assert_eq!(read, graph);
+        assert_eq!(read.find_node("A").and_then(|node| node.attributes.get("tooltip").cloned()), Some("say \\\\\"hi\" \\n".to_string()));
+        assert!(read.nodes().iter().any(|node| node.identifier == "dir\\sub\\\\"));
+
+        // A lone trailing backslash is kept when set, and written with one more so that the content stays readable
+        graph.set_node_attribut("A", "label", "C:\\");
+        assert_eq!(graph.find_node("A").and_then(|node| node.attributes.get("label").cloned()), Some("C:\\".to_string()));
+        let read = DotGraph::try_from(graph.as_dot_content().as_str()).unwrap();
+        assert_eq!(read.find_node("A").and_then(|node| node.attributes.get("label").cloned()), Some("C:\\\\".to_string()));
+    }
+
+    #[test]
+    fn write_then_read() {
+        let input = "digraph Test {A; B [label=test]; A -> B;subgraph{C;D;C->D;}}";
+
+        let graph = DotGraph::try_from(input).unwrap();
+        let content = graph.as_dot_content();
+        assert!(DotGraph::try_from(content.as_str()).is_ok());
+    }
+}
@@ -1,6 +1,8 @@
+use std::fmt::Display;
+
 use crate::TypeRelation;
 
-use super::{attributs::Attributs,  parsing_error::ParsingError};
+use super::{attributs::Attributs, endpoint::Endpoint, parser::Parser, parsing_error::ParsingError, schema::{self, Element, ValidationError}};
 
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -9,62 +11,159 @@ use super::{attributs::Attributs,  parsing_error::ParsingError};
     derive(serde::Serialize, serde::Deserialize)
 )]
 pub struct Edge{
-    pub node_out: NodeId,
-    pub node_in: NodeId,
+    pub node_out: Endpoint,
+    pub node_in: Endpoint,
     pub relation: TypeRelation,
     pub attributs: Attributs
 }
 
-type NodeId = String;
-
 
 impl TryFrom<(&str, &str)> for Edge {
     type Error = ParsingError;
 
     fn try_from(value: (&str, &str)) -> Result<Self, Self::Error> {
-        let splitted = value.0
-            .split_once(value.1)
-            .ok_or(ParsingError::DefaultError("wtf".to_string()))?;
-
-        let left_node= splitted.0.trim().to_string();
         let relation = TypeRelation::try_from(value.1)?;
-        let right_node = splitted.1
-            .split_once("[")
-            .unwrap_or((splitted.1, "")).0
-            .trim()
-            .to_string();
+        Parser::new(value.0)?.parse_edge_statement(relation)
+    }
+}
+
+impl Edge {
+    // Nodes linked by the edge, in a stable order for undirected edges
+    pub(crate) fn pair(&self) -> (String, String) {
+        let pair = (self.node_out.identifier.clone(), self.node_in.identifier.clone());
+        match self.relation {
+            TypeRelation::NonOriente if pair.1 < pair.0 => (pair.1, pair.0),
+            _ => pair
+        }
+    }
+
+    // Typed values of common attributes, falling back to the Graphviz defaults
+    pub fn color(&self) -> Option<&str> {
+        schema::text(&self.attributs, "color", Element::Edge)
+    }
+
+    pub fn style(&self) -> Vec<&str> {
+        schema::list(&self.attributs, "style")
+    }
+
+    pub fn penwidth(&self) -> Option<f64> {
+        schema::number(&self.attributs, "penwidth", Element::Edge)
+    }
+
+    pub fn weight(&self) -> Option<f64> {
+        schema::number(&self.attributs, "weight", Element::Edge)
+    }
+
+    pub fn arrowhead(&self) -> Option<&str> {
+        schema::text(&self.attributs, "arrowhead", Element::Edge)
+    }
+
+    pub fn arrowtail(&self) -> Option<&str> {
+        schema::text(&self.attributs, "arrowtail", Element::Edge)
+    }
+
+    // Graphviz default depends on the graph type, so it is only read when set
+    pub fn dir(&self) -> Option<&str> {
+        self.attributs.get_str("dir")
+    }
 
-        let attributs = match splitted.1.split_once("[") {
-            Some((_, "")) => Attributs::default(),
-            Some((_,b)) => Attributs::try_from(&b.replace("]",""))?,
-            None => Attributs::default()
-        };
+    pub fn constraint(&self) -> Option<bool> {
+        schema::boolean(&self.attributs, "constraint", Element::Edge)
+    }
 
-        Ok(Self{node_out: left_node, node_in: right_node, relation, attributs})
+    pub fn validate(&self) -> Vec<ValidationError> {
+        schema::validate(&self.attributs, Element::Edge, &format!("edge {} {} {}", self.node_out, self.relation, self.node_in))
     }
 }
 
-impl ToString for Edge {
-    fn to_string(&self) -> String {
-        self.node_out.clone() + " " + "->" + " " + &self.node_in + " " + &self.attributs.to_string() + ";"
+impl Display for Edge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {} {};", self.node_out, self.relation, self.node_in, self.attributs)
     }
 }
 
+#[cfg(test)]
 mod tests {
-    
-    
+    use std::collections::HashMap;
+
+    use crate::CompassPoint;
+
+    use super::*;
         
     #[test]
     fn try_from_ok() {
         let mut  map = HashMap::new();
         map.insert("toto".to_string(), "tutu".to_string());
         let combinations :Vec<(&str,Edge)> = vec![
-            ("A->B", Edge{node_out: "A".to_string(), node_in: "B".to_string(), relation: TypeRelation::Oriente, attributs: Attributs::default()}),
-            (" A -> B ", Edge{node_out: "A".to_string(), node_in: "B".to_string(), relation: TypeRelation::Oriente, attributs: Attributs::default()}),
-            ("A->B[toto=tutu]", Edge{node_out: "A".to_string(), node_in: "B".to_string(), relation: TypeRelation::Oriente, attributs: Attributs::from(map)})
+            ("A->B", Edge{node_out: "A".into(), node_in: "B".into(), relation: TypeRelation::Oriente, attributs: Attributs::default()}),
+            (" A -> B ", Edge{node_out: "A".into(), node_in: "B".into(), relation: TypeRelation::Oriente, attributs: Attributs::default()}),
+            ("A->B[toto=tutu]", Edge{node_out: "A".into(), node_in: "B".into(), relation: TypeRelation::Oriente, attributs: Attributs::from(map)})
             ];
             
 
         combinations.iter().for_each(|combinaisons| assert_eq!(Edge::try_from((combinaisons.0, "->")).unwrap(), combinaisons.1));
     } 
-}
\ No newline at end of file
+
+    #[test]
+    fn try_from_ko() {
+        let combinations :Vec<(&str, &str)> = vec![
+            ("A--B", "->"),
+            ("A->", "->"),
+            ("A->B", "=>"),
+            ];
+
+        combinations.iter().for_each(|combinaisons| assert!(Edge::try_from(*combinaisons).is_err()));
+    }
+
+    #[test]
+    fn try_from_ports_ok() {
+        let combinations :Vec<(&str, Endpoint, Endpoint)> = vec![
+            ("A:out:ne -> B:in", Endpoint::new("A", Some("out"), Some(CompassPoint::NorthEast)), Endpoint::new("B", Some("in"), None)),
+            ("A:s -> B:_", Endpoint::new("A", None, Some(CompassPoint::South)), Endpoint::new("B", None, Some(CompassPoint::Any))),
+            ("\"A\":\"p 1\" -> B:north", Endpoint::new("A", Some("p 1"), None), Endpoint::new("B", Some("north"), None)),
+            ];
+
+        combinations.iter().for_each(|combinaisons| {
+            let edge = Edge::try_from((combinaisons.0, "->")).unwrap();
+            assert_eq!(edge.node_out, combinaisons.1);
+            assert_eq!(edge.node_in, combinaisons.2);
+        });
+    }
+
+    #[test]
+    fn try_from_ports_ko() {
+        assert!(Edge::try_from(("A:p1:north -> B", "->")).is_err());
+        assert!(Edge::try_from(("A: -> B", "->")).is_err());
+    }
+
+    #[test]
+    fn to_string_with_ports() {
+        let edge = Edge::try_from(("A:out:ne->B:in [color=red]", "->")).unwrap();
+        assert_eq!(edge.to_string(), "A:out:ne -> B:in [color=red];");
+    }
+
+    #[test]
+    fn to_string_uses_relation() {
+        let edge = Edge::try_from(("A--B", "--")).unwrap();
+        assert_eq!(edge.to_string(), "A -- B ;");
+    }
+
+    #[test]
+    fn typed_getters() {
+        let edge = Edge::try_from(("A->B[color=red, penwidth=2, constraint=false, arrowhead=odiamond]", "->")).unwrap();
+        assert_eq!(edge.color(), Some("red"));
+        assert_eq!(edge.penwidth(), Some(2.0));
+        assert_eq!(edge.weight(), Some(1.0));
+        assert_eq!(edge.constraint(), Some(false));
+        assert_eq!(edge.arrowhead(), Some("odiamond"));
+        assert_eq!(edge.arrowtail(), Some("normal"));
+        assert_eq!(edge.dir(), None);
+        assert!(edge.validate().is_empty());
+
+        let edge = Edge::try_from(("A->B[arrowhead=pointy, shape=box]", "->")).unwrap();
+        assert_eq!(edge.validate().iter().map(ToString::to_string).collect::<Vec<_>>(), vec![
+            "Attribute 'arrowhead' on edge A -> B expects an arrow type, found 'pointy'",
+            "Attribute 'shape' does not apply to edge A -> B",
+            ]);
+    }
+}
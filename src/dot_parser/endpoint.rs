@@ -0,0 +1,127 @@
+use std::fmt::Display;
+
+use super::{lexer::quote_id, parsing_error::ParsingError};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum CompassPoint {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+    Center,
+    Any
+}
+
+impl TryFrom<&str> for CompassPoint {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "n" => Ok(CompassPoint::North),
+            "ne" => Ok(CompassPoint::NorthEast),
+            "e" => Ok(CompassPoint::East),
+            "se" => Ok(CompassPoint::SouthEast),
+            "s" => Ok(CompassPoint::South),
+            "sw" => Ok(CompassPoint::SouthWest),
+            "w" => Ok(CompassPoint::West),
+            "nw" => Ok(CompassPoint::NorthWest),
+            "c" => Ok(CompassPoint::Center),
+            "_" => Ok(CompassPoint::Any),
+            other => Err(ParsingError::DefaultError(format!("Unknown compass point {}", other)))
+        }
+    }
+}
+
+impl Display for CompassPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            CompassPoint::North => "n",
+            CompassPoint::NorthEast => "ne",
+            CompassPoint::East => "e",
+            CompassPoint::SouthEast => "se",
+            CompassPoint::South => "s",
+            CompassPoint::SouthWest => "sw",
+            CompassPoint::West => "w",
+            CompassPoint::NorthWest => "nw",
+            CompassPoint::Center => "c",
+            CompassPoint::Any => "_",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+// Node reference used by an edge: node_id [ ':' port ] [ ':' compass_pt ]
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Endpoint {
+    pub identifier: String,
+    pub port: Option<String>,
+    pub compass: Option<CompassPoint>
+}
+
+impl Endpoint {
+    pub fn new(identifier: &str, port: Option<&str>, compass: Option<CompassPoint>) -> Self {
+        Endpoint { identifier: identifier.to_string(), port: port.map(str::to_string), compass }
+    }
+}
+
+impl From<&str> for Endpoint {
+    fn from(value: &str) -> Self {
+        Endpoint::new(value, None, None)
+    }
+}
+
+impl From<String> for Endpoint {
+    fn from(value: String) -> Self {
+        Endpoint { identifier: value, port: None, compass: None }
+    }
+}
+
+impl Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", quote_id(&self.identifier))?;
+        if let Some(port) = &self.port {
+            write!(f, ":{}", quote_id(port))?;
+        }
+        if let Some(compass) = &self.compass {
+            write!(f, ":{}", compass)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_string_ok() {
+        let combinations :Vec<(Endpoint, &str)> = vec![
+            (Endpoint::from("A"), "A"),
+            (Endpoint::new("A", Some("p1"), None), "A:p1"),
+            (Endpoint::new("A", None, Some(CompassPoint::NorthEast)), "A:ne"),
+            (Endpoint::new("A B", Some("out"), Some(CompassPoint::Any)), "\"A B\":out:_"),
+            (Endpoint::from("say \"hi\""), "\"say \\\"hi\\\"\""),
+            ];
+
+        combinations.iter().for_each(|combinaisons| assert_eq!(combinaisons.0.to_string(), combinaisons.1));
+    }
+
+    #[test]
+    fn compass_point_round_trip() {
+        ["n", "ne", "e", "se", "s", "sw", "w", "nw", "c", "_"].iter()
+            .for_each(|compass| assert_eq!(CompassPoint::try_from(*compass).unwrap().to_string(), *compass));
+        assert!(CompassPoint::try_from("north").is_err());
+    }
+}
@@ -1,7 +1,9 @@
-use super::parsing_error::ParsingError;
+use std::fmt::Display;
 
+use super::{parsing_error::ParsingError, type_relation::TypeRelation};
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 #[cfg_attr(
     feature = "serde",
     derive(serde::Serialize, serde::Deserialize)
@@ -15,8 +17,8 @@ impl TryFrom<&str> for GraphType {
     type Error = ParsingError;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            "digraph" => Ok(GraphType::Digraph),
-            "graph" => Ok(GraphType::Graph),
+            digraph if digraph.eq_ignore_ascii_case("digraph") => Ok(GraphType::Digraph),
+            graph if graph.eq_ignore_ascii_case("graph") => Ok(GraphType::Graph),
             other => Err(ParsingError::DefaultError(other.to_string()))
         }
     }
@@ -31,11 +33,39 @@ impl GraphType {
     }
 }
 
-impl ToString for GraphType {
-    fn to_string(&self) -> String {
+impl Display for GraphType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            GraphType::Graph => "graph".to_string(),
-            GraphType::Digraph => "digraph".to_string() ,
+            GraphType::Graph => write!(f, "graph"),
+            GraphType::Digraph => write!(f, "digraph"),
+        }
+    }
+}
+
+impl From<GraphType> for TypeRelation {
+    fn from(value: GraphType) -> Self {
+        match value {
+            GraphType::Graph => TypeRelation::NonOriente,
+            GraphType::Digraph => TypeRelation::Oriente,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graph_type_ignore_case() {
+        let combinations :Vec<(&str, Option<GraphType>)> = vec![
+            ("digraph", Some(GraphType::Digraph)),
+            ("Digraph", Some(GraphType::Digraph)),
+            ("DIGRAPH", Some(GraphType::Digraph)),
+            ("graph", Some(GraphType::Graph)),
+            ("Graph", Some(GraphType::Graph)),
+            ("subgraph", None),
+            ];
+
+        combinations.into_iter().for_each(|combinaisons| assert_eq!(GraphType::try_from(combinaisons.0).ok(), combinaisons.1));
+    }
+}
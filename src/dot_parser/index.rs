@@ -0,0 +1,134 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{dot_graph::DotGraph, edge::Edge, type_relation::TypeRelation};
+
+// Adjacency of a graph, built once to answer neighbourhood queries without scanning every edge.
+// Undirected edges go both ways, so they are outgoing and incoming edges of both their nodes
+pub struct GraphIndex<'a> {
+    edges: Vec<&'a Edge>,
+    outgoing: HashMap<&'a str, Vec<usize>>,
+    incoming: HashMap<&'a str, Vec<usize>>,
+    links: HashSet<(&'a str, &'a str)>,
+}
+
+impl<'a> GraphIndex<'a> {
+    pub fn new(graph: &'a DotGraph) -> Self {
+        let mut index = GraphIndex { edges: vec![], outgoing: HashMap::new(), incoming: HashMap::new(), links: HashSet::new() };
+        graph.edge_refs().into_iter().for_each(|edge| index.push(edge));
+        index
+    }
+
+    fn push(&mut self, edge: &'a Edge) {
+        let position = self.edges.len();
+        let (node_out, node_in) = (edge.node_out.identifier.as_str(), edge.node_in.identifier.as_str());
+        self.edges.push(edge);
+        self.link(node_out, node_in, position);
+        if edge.relation == TypeRelation::NonOriente && node_out != node_in {
+            self.link(node_in, node_out, position);
+        }
+    }
+
+    fn link(&mut self, node_out: &'a str, node_in: &'a str, position: usize) {
+        self.outgoing.entry(node_out).or_default().push(position);
+        self.incoming.entry(node_in).or_default().push(position);
+        self.links.insert((node_out, node_in));
+    }
+
+    // Edges leaving the node, in declaration order
+    pub fn outgoing_edges(&self, identifier: &str) -> Vec<&'a Edge> {
+        self.edges_at(&self.outgoing, identifier)
+    }
+
+    // Edges reaching the node, in declaration order
+    pub fn incoming_edges(&self, identifier: &str) -> Vec<&'a Edge> {
+        self.edges_at(&self.incoming, identifier)
+    }
+
+    // Nodes reached from the node, once each
+    pub fn successors(&self, identifier: &str) -> Vec<&'a str> {
+        self.neighbours(&self.outgoing, identifier, |edge| &edge.node_in.identifier, |edge| &edge.node_out.identifier)
+    }
+
+    // Nodes reaching the node, once each
+    pub fn predecessors(&self, identifier: &str) -> Vec<&'a str> {
+        self.neighbours(&self.incoming, identifier, |edge| &edge.node_out.identifier, |edge| &edge.node_in.identifier)
+    }
+
+    pub fn out_degree(&self, identifier: &str) -> usize {
+        self.outgoing.get(identifier).map(Vec::len).unwrap_or_default()
+    }
+
+    pub fn in_degree(&self, identifier: &str) -> usize {
+        self.incoming.get(identifier).map(Vec::len).unwrap_or_default()
+    }
+
+    // Whether an edge goes from one node to the other, either way for undirected edges
+    pub fn has_edge(&self, node_out: &str, node_in: &str) -> bool {
+        self.links.contains(&(node_out, node_in))
+    }
+
+    fn edges_at(&self, adjacency: &HashMap<&'a str, Vec<usize>>, identifier: &str) -> Vec<&'a Edge> {
+        adjacency.get(identifier).into_iter().flatten().map(|position| self.edges[*position]).collect()
+    }
+
+    // The far end of each edge, the near one being reversed for undirected edges seen from the other side
+    fn neighbours(&self, adjacency: &HashMap<&'a str, Vec<usize>>, identifier: &str, far: fn(&'a Edge) -> &'a String, near: fn(&'a Edge) -> &'a String) -> Vec<&'a str> {
+        let mut seen = HashSet::new();
+        self.edges_at(adjacency, identifier).into_iter()
+            .map(|edge| match far(edge) == identifier && near(edge) != identifier {
+                true => near(edge).as_str(),
+                false => far(edge).as_str()
+            })
+            .filter(|neighbour| seen.insert(*neighbour))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directed_queries() {
+        let graph = DotGraph::try_from("digraph Test {A -> B; A -> C; subgraph s {B -> C; {C -> A; A -> B}} D}").unwrap();
+        let index = graph.index();
+
+        assert_eq!(index.successors("A"), vec!["B", "C"]);
+        assert_eq!(index.predecessors("A"), vec!["C"]);
+        assert_eq!(index.predecessors("C"), vec!["A", "B"]);
+        assert_eq!((index.out_degree("A"), index.in_degree("A")), (3, 1));
+        assert_eq!((index.out_degree("D"), index.in_degree("D")), (0, 0));
+        assert_eq!(index.outgoing_edges("B").iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["B -> C ;"]);
+        assert_eq!(index.incoming_edges("B").len(), 2);
+        assert!(index.has_edge("A", "B"));
+        assert!(!index.has_edge("B", "A"));
+        assert!(index.successors("Z").is_empty());
+    }
+
+    #[test]
+    fn hub_queries() {
+        let content = (0..2000).fold("digraph Test {".to_string(), |content, position| content + &format!("hub -> n{}; n{} -> hub; hub -> n{};", position % 500, position, position % 500)) + "}";
+        let graph = DotGraph::try_from(content.as_str()).unwrap();
+        let index = graph.index();
+
+        assert_eq!(index.out_degree("hub"), 4000);
+        assert_eq!(index.successors("hub").len(), 500);
+        assert_eq!(index.successors("hub")[..3], ["n0", "n1", "n2"]);
+        assert_eq!(index.predecessors("hub").len(), 2000);
+    }
+
+    #[test]
+    fn undirected_queries() {
+        let graph = DotGraph::try_from("graph Test {A -- B; C -- A; B -- B}").unwrap();
+        let index = graph.index();
+
+        assert_eq!(index.successors("A"), vec!["B", "C"]);
+        assert_eq!(index.predecessors("A"), vec!["B", "C"]);
+        assert_eq!(index.successors("B"), vec!["A", "B"]);
+        assert_eq!((index.out_degree("A"), index.in_degree("A")), (2, 2));
+        assert_eq!((index.out_degree("B"), index.in_degree("B")), (2, 2));
+        assert!(index.has_edge("A", "C"));
+        assert!(index.has_edge("C", "A"));
+        assert!(!index.has_edge("B", "C"));
+    }
+}
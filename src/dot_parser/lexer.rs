@@ -0,0 +1,542 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use super::{parsing_error::ParsingError, span::{LineIndex, Span}, type_relation::TypeRelation};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum TokenKind {
+    Id(String),
+    Numeral(String),
+    QuotedString { raw: String, value: String },
+    HtmlString(String),
+    EdgeOp(TypeRelation),
+    Strict,
+    Graph,
+    Digraph,
+    Node,
+    Edge,
+    Subgraph,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Equal,
+    Semicolon,
+    Comma,
+    Colon,
+}
+
+impl TokenKind {
+    // Text of the token as found in the source
+    pub fn text(&self) -> String {
+        match self {
+            TokenKind::Id(text)
+            | TokenKind::Numeral(text)
+            | TokenKind::QuotedString { raw: text, .. }
+            | TokenKind::HtmlString(text) => text.clone(),
+            TokenKind::EdgeOp(relation) => relation.to_string(),
+            TokenKind::Strict => "strict".to_string(),
+            TokenKind::Graph => "graph".to_string(),
+            TokenKind::Digraph => "digraph".to_string(),
+            TokenKind::Node => "node".to_string(),
+            TokenKind::Edge => "edge".to_string(),
+            TokenKind::Subgraph => "subgraph".to_string(),
+            TokenKind::LeftBrace => "{".to_string(),
+            TokenKind::RightBrace => "}".to_string(),
+            TokenKind::LeftBracket => "[".to_string(),
+            TokenKind::RightBracket => "]".to_string(),
+            TokenKind::Equal => "=".to_string(),
+            TokenKind::Semicolon => ";".to_string(),
+            TokenKind::Comma => ",".to_string(),
+            TokenKind::Colon => ":".to_string(),
+        }
+    }
+
+    // ID meaning, without the quotes or HTML brackets
+    pub fn value(&self) -> String {
+        match self {
+            TokenKind::QuotedString { value, .. } => value.clone(),
+            TokenKind::HtmlString(text) => text[1..text.len() - 1].to_string(),
+            other => other.text()
+        }
+    }
+
+    // Any of the ID forms allowed by the grammar
+    pub fn is_id(&self) -> bool {
+        matches!(self, TokenKind::Id(_) | TokenKind::Numeral(_) | TokenKind::QuotedString { .. } | TokenKind::HtmlString(_))
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span
+}
+
+pub fn tokenize(content: &str) -> Result<Vec<Token>, ParsingError> {
+    Lexer::new(content).tokenize()
+}
+
+// Tokenize everything possible, skipping invalid content instead of stopping
+pub fn tokenize_recovering(content: &str) -> (Vec<Token>, Vec<ParsingError>) {
+    Lexer::new(content).tokenize_recovering()
+}
+
+struct Lexer<'a> {
+    content: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    index: LineIndex<'a>
+}
+
+impl<'a> Lexer<'a> {
+    fn new(content: &'a str) -> Self {
+        Lexer { content, chars: content.char_indices().peekable(), index: LineIndex::new(content) }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>, ParsingError> {
+        let mut tokens = vec![];
+        while let Some(token) = self.next_token()? {
+            tokens.push(token);
+        }
+        Ok(tokens)
+    }
+
+    fn tokenize_recovering(mut self) -> (Vec<Token>, Vec<ParsingError>) {
+        let mut tokens = vec![];
+        let mut errors = vec![];
+        loop {
+            match self.next_token() {
+                Ok(Some(token)) => tokens.push(token),
+                Ok(None) => return (tokens, errors),
+                Err(error) => {
+                    self.resume_after(&error);
+                    errors.push(error);
+                }
+            }
+        }
+    }
+
+    // Unterminated strings are skipped up to the end of their line, unterminated comments up to the end
+    // of the content, invalid numerals as a whole, anything else by one char
+    fn resume_after(&mut self, error: &ParsingError) {
+        let start = error.span().map(|span| span.offset).unwrap_or_default();
+        let resume = match error {
+            ParsingError::UnterminatedString { .. } | ParsingError::UnterminatedHtmlString { .. } => start + self.first_line(start).len(),
+            ParsingError::UnterminatedComment { .. } => self.content.len(),
+            ParsingError::InvalidNumeral { token, .. } => start + token.len(),
+            _ => start + self.content[start..].chars().next().map(char::len_utf8).unwrap_or_default()
+        };
+
+        self.chars = self.content.char_indices().peekable();
+        while self.chars.next_if(|(position, _)| *position < resume).is_some() {}
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, ParsingError> {
+        self.skip_whitespaces_and_comments()?;
+
+        let Some(&(position, char)) = self.chars.peek() else {
+            return Ok(None);
+        };
+
+        let kind = match char {
+            '{' => self.single(TokenKind::LeftBrace),
+            '}' => self.single(TokenKind::RightBrace),
+            '[' => self.single(TokenKind::LeftBracket),
+            ']' => self.single(TokenKind::RightBracket),
+            '=' => self.single(TokenKind::Equal),
+            ';' => self.single(TokenKind::Semicolon),
+            ',' => self.single(TokenKind::Comma),
+            ':' => self.single(TokenKind::Colon),
+            '"' => self.quoted_string(position)?,
+            '<' => self.html_string(position)?,
+            '-' => self.edge_op_or_numeral(position)?,
+            c if c == '.' || c.is_ascii_digit() => self.numeral(position)?,
+            c if is_id_start(c) => self.identifier(position),
+            other => return Err(ParsingError::UnexpectedCharacter { span: self.index.span(position, other.len_utf8()), token: other.to_string() })
+        };
+
+        let length = self.chars.peek().map(|(end, _)| *end).unwrap_or(self.content.len()) - position;
+        Ok(Some(Token { kind, span: self.index.span(position, length) }))
+    }
+
+    // Line comments, block comments and '#' preprocessor lines are discarded
+    fn skip_whitespaces_and_comments(&mut self) -> Result<(), ParsingError> {
+        loop {
+            while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+            let Some(&(position, _)) = self.chars.peek() else {
+                return Ok(());
+            };
+            let rest = self.rest();
+            if rest.starts_with("//") || (rest.starts_with('#') && self.at_line_start(position)) {
+                while self.chars.next_if(|(_, c)| *c != '\n').is_some() {}
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                let Some(end) = comment.find("*/") else {
+                    return Err(ParsingError::UnterminatedComment { span: self.index.span(position, 2), token: "/*".to_string() });
+                };
+                let resume = position + end + 4;
+                while self.chars.next_if(|(position, _)| *position < resume).is_some() {}
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn at_line_start(&self, position: usize) -> bool {
+        self.content[..position].ends_with('\n') || position == 0
+    }
+
+    fn rest(&mut self) -> &'a str {
+        match self.chars.peek() {
+            Some(&(position, _)) => &self.content[position..],
+            None => ""
+        }
+    }
+
+    fn single(&mut self, kind: TokenKind) -> TokenKind {
+        self.chars.next();
+        kind
+    }
+
+    // A quoted string and the ones concatenated to it with '+'
+    fn quoted_string(&mut self, start: usize) -> Result<TokenKind, ParsingError> {
+        let mut value = self.quoted_piece(start)?;
+        while let Some(next) = self.concatenation() {
+            value += &self.quoted_piece(next)?;
+        }
+
+        let end = self.chars.peek().map(|(end, _)| *end).unwrap_or(self.content.len());
+        Ok(TokenKind::QuotedString { raw: self.content[start..end].to_string(), value })
+    }
+
+    // Start of the next string after a '+', the lexer is left untouched when there is none
+    fn concatenation(&mut self) -> Option<usize> {
+        let checkpoint = self.chars.clone();
+        let next = self.skip_whitespaces_and_comments().ok()
+            .filter(|_| self.chars.next_if(|(_, c)| *c == '+').is_some())
+            .and_then(|_| self.skip_whitespaces_and_comments().ok())
+            .and_then(|_| self.chars.peek().filter(|(_, c)| *c == '"').map(|(position, _)| *position));
+
+        if next.is_none() {
+            self.chars = checkpoint;
+        }
+        next
+    }
+
+    // Only escaped quotes and escaped line breaks are decoded, other escapes are kept for Graphviz
+    fn quoted_piece(&mut self, start: usize) -> Result<String, ParsingError> {
+        self.chars.next();
+        let mut value = String::new();
+        while let Some((_, char)) = self.chars.next() {
+            match char {
+                '\\' => match self.chars.next_if(|(_, c)| matches!(c, '"' | '\\' | '\r' | '\n')) {
+                    Some((_, '"')) => value.push('"'),
+                    Some((_, '\\')) => value.push_str("\\\\"),
+                    Some((_, '\r')) => { self.chars.next_if(|(_, c)| *c == '\n'); },
+                    Some(_) => {},
+                    None => value.push('\\')
+                },
+                '"' => return Ok(value),
+                other => value.push(other)
+            }
+        }
+        Err(ParsingError::UnterminatedString { span: self.index.span(start, 1), token: self.first_line(start) })
+    }
+
+    fn html_string(&mut self, start: usize) -> Result<TokenKind, ParsingError> {
+        let mut depth = 0;
+        for (position, char) in self.chars.by_ref() {
+            match char {
+                '<' => depth += 1,
+                '>' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(TokenKind::HtmlString(self.content[start..=position].to_string()));
+                    }
+                },
+                _ => {}
+            }
+        }
+        Err(ParsingError::UnterminatedHtmlString { span: self.index.span(start, 1), token: self.first_line(start) })
+    }
+
+    fn edge_op_or_numeral(&mut self, start: usize) -> Result<TokenKind, ParsingError> {
+        let rest = self.rest();
+        if rest.starts_with("->") || rest.starts_with("--") {
+            let relation = TypeRelation::try_from(&rest[..2])?;
+            self.chars.next();
+            self.chars.next();
+            return Ok(TokenKind::EdgeOp(relation));
+        }
+
+        match rest[1..].chars().next() {
+            Some(c) if c == '.' || c.is_ascii_digit() => self.numeral(start),
+            _ => Err(ParsingError::UnknownEdgeOperator { span: self.index.span(start, 1), token: self.first_line(start).split_whitespace().next().unwrap_or("-").to_string() })
+        }
+    }
+
+    // Rest of the line from start, to show an offending token
+    fn first_line(&self, start: usize) -> String {
+        self.content[start..].lines().next().unwrap_or_default().to_string()
+    }
+
+    // A numeral needs at least one digit, '.' and '-.' alone are rejected
+    fn numeral(&mut self, start: usize) -> Result<TokenKind, ParsingError> {
+        self.chars.next_if(|(_, c)| *c == '-');
+        let mut seen_dot = false;
+        let mut seen_digit = false;
+        let mut end = start + 1;
+        while let Some((position, char)) = self.chars.next_if(|(_, c)| c.is_ascii_digit() || (*c == '.' && !seen_dot)) {
+            seen_dot |= char == '.';
+            seen_digit |= char.is_ascii_digit();
+            end = position + 1;
+        }
+        let text = self.content[start..end].to_string();
+        match seen_digit {
+            true => Ok(TokenKind::Numeral(text)),
+            false => Err(ParsingError::InvalidNumeral { span: self.index.span(start, end - start), token: text })
+        }
+    }
+
+    fn identifier(&mut self, start: usize) -> TokenKind {
+        let mut end = start;
+        while let Some((position, char)) = self.chars.next_if(|(_, c)| is_id_char(*c)) {
+            end = position + char.len_utf8();
+        }
+        let text = &self.content[start..end];
+        match text.to_ascii_lowercase().as_str() {
+            "strict" => TokenKind::Strict,
+            "graph" => TokenKind::Graph,
+            "digraph" => TokenKind::Digraph,
+            "node" => TokenKind::Node,
+            "edge" => TokenKind::Edge,
+            "subgraph" => TokenKind::Subgraph,
+            _ => TokenKind::Id(text.to_string())
+        }
+    }
+}
+
+// Write an ID back as DOT, quoting it when it is not a plain identifier or numeral.
+// An odd run of backslashes before a quote, a line break or the end cannot be written as is and gets one more
+pub fn quote_id(value: &str) -> String {
+    let is_keyword = ["strict", "graph", "digraph", "node", "edge", "subgraph"].contains(&value.to_ascii_lowercase().as_str());
+    let mut chars = value.chars();
+    let is_identifier = chars.next().is_some_and(is_id_start) && chars.all(is_id_char);
+
+    match (is_identifier && !is_keyword) || is_numeral(value) {
+        true => value.to_string(),
+        false => format!("\"{}\"", escape_backslashes(value).replace('"', "\\\""))
+    }
+}
+
+// Double the odd runs of backslashes that would otherwise escape a quote or a line break, or the closing quote at the end.
+// Reading the quoted string back gives this value, \\ being kept as is
+fn escape_backslashes(value: &str) -> String {
+    let mut escaped = String::new();
+    let mut backslashes = 0;
+    for char in value.chars().map(Some).chain(std::iter::once(None)) {
+        match char {
+            Some('\\') => backslashes += 1,
+            other => {
+                let escaping = matches!(other, None | Some('"' | '\r' | '\n')) && backslashes % 2 == 1;
+                escaped.push_str(&"\\".repeat(backslashes + usize::from(escaping)));
+                escaped.extend(other);
+                backslashes = 0;
+            }
+        }
+    }
+    escaped
+}
+
+pub(crate) fn is_numeral(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let mut parts = digits.splitn(2, '.');
+    let integer = parts.next().unwrap_or_default();
+    let decimals = parts.next();
+    let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+
+    all_digits(integer) && decimals.is_none_or(all_digits) && (!integer.is_empty() || decimals.is_some_and(|decimals| !decimals.is_empty()))
+}
+
+fn is_id_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_id_char(c: char) -> bool {
+    is_id_start(c) || c.is_ascii_digit()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(content: &str) -> Vec<TokenKind> {
+        tokenize(content).unwrap().into_iter().map(|token| token.kind).collect()
+    }
+
+    fn quoted(raw: &str, value: &str) -> TokenKind {
+        TokenKind::QuotedString { raw: raw.to_string(), value: value.to_string() }
+    }
+
+    #[test]
+    fn tokenize_ok() {
+        let combinations :Vec<(&str, Vec<TokenKind>)> = vec![
+            ("digraph G {}", vec![TokenKind::Digraph, TokenKind::Id("G".to_string()), TokenKind::LeftBrace, TokenKind::RightBrace]),
+            ("A->B", vec![TokenKind::Id("A".to_string()), TokenKind::EdgeOp(TypeRelation::Oriente), TokenKind::Id("B".to_string())]),
+            ("A -- -1.5", vec![TokenKind::Id("A".to_string()), TokenKind::EdgeOp(TypeRelation::NonOriente), TokenKind::Numeral("-1.5".to_string())]),
+            ("[w=.5;x=2]", vec![TokenKind::LeftBracket, TokenKind::Id("w".to_string()), TokenKind::Equal, TokenKind::Numeral(".5".to_string()),
+                TokenKind::Semicolon, TokenKind::Id("x".to_string()), TokenKind::Equal, TokenKind::Numeral("2".to_string()), TokenKind::RightBracket]),
+            ("label=\"a; b -> c {x}\"", vec![TokenKind::Id("label".to_string()), TokenKind::Equal, quoted("\"a; b -> c {x}\"", "a; b -> c {x}")]),
+            ("\"say \\\"hi\\\"\"", vec![quoted("\"say \\\"hi\\\"\"", "say \"hi\"")]),
+            ("<<b>bold</b>>", vec![TokenKind::HtmlString("<<b>bold</b>>".to_string())]),
+            ("A:p1:ne", vec![TokenKind::Id("A".to_string()), TokenKind::Colon, TokenKind::Id("p1".to_string()), TokenKind::Colon, TokenKind::Id("ne".to_string())]),
+            ("A // comment\r\nB", vec![TokenKind::Id("A".to_string()), TokenKind::Id("B".to_string())]),
+            ("A /* multi\r\nline -> { */ B/**/C", vec![TokenKind::Id("A".to_string()), TokenKind::Id("B".to_string()), TokenKind::Id("C".to_string())]),
+            ("# 1 \"graph.gv\"\r\nA\n#define X\nB", vec![TokenKind::Id("A".to_string()), TokenKind::Id("B".to_string())]),
+            ("URL=\"http://x/*y*/\"", vec![TokenKind::Id("URL".to_string()), TokenKind::Equal, quoted("\"http://x/*y*/\"", "http://x/*y*/")]),
+            ("<<a href=\"http://x\">#1</a>>", vec![TokenKind::HtmlString("<<a href=\"http://x\">#1</a>>".to_string())]),
+            ("été_2", vec![TokenKind::Id("été_2".to_string())]),
+            ];
+
+        combinations.iter().for_each(|combinaisons| assert_eq!(kinds(combinaisons.0), combinaisons.1));
+    }
+
+    #[test]
+    fn tokenize_concatenation() {
+        let tokens = tokenize("A [label=\"a\" +\n\"b\"] \"c\" + D").unwrap_err();
+        assert!(matches!(tokens, ParsingError::UnexpectedCharacter { .. }));
+
+        let tokens = tokenize("[label=\"a\" +\n\"b\"] \"c\"").unwrap();
+        assert_eq!(tokens[3].kind, quoted("\"a\" +\n\"b\"", "ab"));
+        assert_eq!((tokens[3].span.line, tokens[3].span.length), (1, 9));
+        assert_eq!(tokens[5].kind, quoted("\"c\"", "c"));
+    }
+
+    #[test]
+    fn tokenize_keywords_ignore_case() {
+        assert_eq!(kinds("STRICT DiGraph Node EDGE SubGraph Graph"),
+            vec![TokenKind::Strict, TokenKind::Digraph, TokenKind::Node, TokenKind::Edge, TokenKind::Subgraph, TokenKind::Graph]);
+        assert_eq!(kinds("graphs"), vec![TokenKind::Id("graphs".to_string())]);
+    }
+
+    #[test]
+    fn value_ok() {
+        let combinations :Vec<(&str, &str)> = vec![
+            ("name", "name"),
+            ("-1.5", "-1.5"),
+            ("\"My Graph\"", "My Graph"),
+            ("\"say \\\"hi\\\"\"", "say \"hi\""),
+            ("\"a\\nb\"", "a\\nb"),
+            ("\"a\\\\\"", "a\\\\"),
+            ("\"long \\\nline\"", "long line"),
+            ("\"long \\\r\nline\"", "long line"),
+            ("\"a\" + \"b\"", "ab"),
+            ("\"a\"+\n  // split\n  \"b\" /* end */ + \"c\"", "abc"),
+            ("<<b>x</b>>", "<b>x</b>"),
+            ];
+
+        combinations.iter().for_each(|combinaisons| assert_eq!(kinds(combinaisons.0)[0].value(), combinaisons.1));
+    }
+
+    #[test]
+    fn quote_id_ok() {
+        let combinations :Vec<(&str, &str)> = vec![
+            ("name_2", "name_2"),
+            ("-1.5", "-1.5"),
+            (".5", ".5"),
+            ("My Graph", "\"My Graph\""),
+            ("2name", "\"2name\""),
+            ("say \"hi\"", "\"say \\\"hi\\\"\""),
+            ("Graph", "\"Graph\""),
+            ("", "\"\""),
+            ("-", "\"-\""),
+            ("C:\\", "\"C:\\\\\""),
+            ("C:\\\\", "\"C:\\\\\""),
+            ("a\\b", "\"a\\b\""),
+            ("a\\\"b", "\"a\\\\\\\"b\""),
+            ("a\\\nb", "\"a\\\\\nb\""),
+            ];
+
+        combinations.iter().for_each(|combinaisons| assert_eq!(quote_id(combinaisons.0), combinaisons.1));
+    }
+
+    #[test]
+    fn quote_id_read_back() {
+        let combinations :Vec<(&str, &str)> = vec![
+            ("C:\\", "C:\\\\"),
+            ("C:\\\\", "C:\\\\"),
+            ("dir\\sub\\", "dir\\sub\\\\"),
+            ("a\\b", "a\\b"),
+            ("a\\\\\"b\"", "a\\\\\"b\""),
+            ("a\\\"b", "a\\\\\"b"),
+            ("a\\\nb", "a\\\\\nb"),
+            ];
+
+        combinations.iter().for_each(|combinaisons| {
+            let quoted = quote_id(combinaisons.0);
+            assert_eq!(kinds(&quoted)[0].value(), combinaisons.1, "{}", quoted);
+            assert_eq!(quote_id(combinaisons.1), quoted);
+        });
+    }
+
+    #[test]
+    fn tokenize_ko() {
+        let combinations :Vec<&str> = vec![
+            "\"never closed",
+            "<<b>never closed</b>",
+            "A - B",
+            "A $ B",
+            "A # not at line start",
+            "A /* never closed",
+            "\"a\" + \"never closed",
+            "A + B",
+            "A [w=.]",
+            "A [w=-.]",
+            ];
+
+        combinations.iter().for_each(|combinaisons| assert!(tokenize(combinaisons).is_err()));
+    }
+
+    #[test]
+    fn tokenize_recovering_ok() {
+        let (tokens, errors) = tokenize_recovering("A $ B;\nC [label=\"oops];\nD - E");
+        let kinds = tokens.into_iter().map(|token| token.kind.text()).collect::<Vec<_>>();
+        assert_eq!(kinds, vec!["A", "B", ";", "C", "[", "label", "=", "D", "E"]);
+        assert_eq!(errors.iter().map(|error| error.span().unwrap().line).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn tokenize_recovering_unterminated_comment() {
+        let (tokens, errors) = tokenize_recovering("A;\nB /* never closed\nC");
+        assert_eq!(tokens.into_iter().map(|token| token.kind.text()).collect::<Vec<_>>(), vec!["A", ";", "B"]);
+        assert!(matches!(errors[..], [ParsingError::UnterminatedComment { .. }]));
+        assert_eq!(errors[0].span().map(|span| (span.line, span.column)), Some((2, 3)));
+    }
+
+    #[test]
+    fn token_spans() {
+        let spans = tokenize("A -> \"B\"\r\n  C").unwrap().into_iter().map(|token| (token.span.offset, token.span.length, token.span.line, token.span.column)).collect::<Vec<_>>();
+        assert_eq!(spans, vec![(0, 1, 1, 1), (2, 2, 1, 3), (5, 3, 1, 6), (12, 1, 2, 3)]);
+    }
+
+    #[test]
+    fn tokenize_ko_spans() {
+        let error = tokenize("A;\nB [label=\"never closed]").unwrap_err();
+        assert!(matches!(error, ParsingError::UnterminatedString { .. }));
+        assert_eq!(error.span().map(|span| (span.line, span.column)), Some((2, 10)));
+        assert_eq!(error.token(), Some("\"never closed]"));
+
+        let error = tokenize("A -> B\n  A - C").unwrap_err();
+        assert!(matches!(error, ParsingError::UnknownEdgeOperator { .. }));
+        assert_eq!(error.span().map(|span| (span.line, span.column)), Some((2, 5)));
+
+        let error = tokenize("A ? B").unwrap_err();
+        assert!(matches!(error, ParsingError::UnexpectedCharacter { .. }));
+        assert_eq!(error.token(), Some("?"));
+
+        let error = tokenize("A [w=-.]").unwrap_err();
+        assert!(matches!(error, ParsingError::InvalidNumeral { .. }));
+        assert_eq!(error.span().map(|span| (span.column, span.length)), Some((6, 2)));
+        assert_eq!(error.token(), Some("-."));
+    }
+}
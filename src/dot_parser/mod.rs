@@ -5,6 +5,16 @@ pub mod edge;
 pub mod attribut;
 pub mod parsing_error;
 pub mod attributs;
+pub mod attribut_value;
 pub mod type_relation;
+pub mod lexer;
+pub mod parser;
+pub mod span;
+pub mod diagnostic;
+pub mod endpoint;
+pub mod schema;
+pub mod builder;
+pub mod index;
+pub mod provenance;
 
 pub mod petgraph;
\ No newline at end of file
@@ -1,5 +1,7 @@
+use std::fmt::Display;
+
 use crate::Attributs;
-use super::parsing_error::ParsingError;
+use super::{lexer::quote_id, parser::Parser, parsing_error::ParsingError, schema::{self, Element, ValidationError}};
 
 #[derive(PartialEq, Eq, Debug, Clone, Default)]
 #[cfg_attr(
@@ -11,61 +13,96 @@ pub struct Node{
     pub attributes: Attributs
 }
 
+// A node of the graph, declared by a node statement or only used by edges
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct GraphNode {
+    // Attributes of every node statement of the node
+    pub node: Node,
+    pub implicit: bool,
+    // Position of the subgraph where the node first appears, in sous_graphes at each depth
+    pub sous_graphe: Vec<usize>
+}
+
 impl TryFrom<&String> for Node {
     type Error = ParsingError;
 
     fn try_from(value: &String) -> Result<Self, Self::Error> {
-
-        let split = value.split_once("[").unwrap_or((value, ""));
-        let attr = match split.1.is_empty() {
-            true => Attributs::default(),
-            false => Attributs::try_from(&split.1.replace("]",""))?
-        };
-        
-        Ok(Self{identifier: split.0.trim().to_string(), attributes: attr})
+        Parser::new(value)?.parse_node_statement()
     }
 }
 
 
 
 impl Node {
+    // The identifier is kept as given, see quote_id for the backslashes DOT cannot write
     pub fn new(identifier: &str, attributes: Attributs) -> Self {
         Self{
             identifier: identifier.to_string(), 
             attributes
         }
     }
-}
 
-impl ToString for Node {
-    fn to_string(&self) -> String {
-        let mut content = self.identifier.clone();
+    // Typed values of common attributes, falling back to the Graphviz defaults
+    pub fn shape(&self) -> Option<&str> {
+        schema::text(&self.attributes, "shape", Element::Node)
+    }
 
-        let attributes_to_string = self.attributes.to_string();
+    pub fn color(&self) -> Option<&str> {
+        schema::text(&self.attributes, "color", Element::Node)
+    }
 
-        content = content + &attributes_to_string +  ";";
-        content
+    pub fn fillcolor(&self) -> Option<&str> {
+        schema::text(&self.attributes, "fillcolor", Element::Node)
     }
-}
 
-mod test {
-    
+    pub fn style(&self) -> Vec<&str> {
+        schema::list(&self.attributes, "style")
+    }
 
-    
+    pub fn width(&self) -> Option<f64> {
+        schema::number(&self.attributes, "width", Element::Node)
+    }
 
-    
+    pub fn height(&self) -> Option<f64> {
+        schema::number(&self.attributes, "height", Element::Node)
+    }
 
-    
+    pub fn penwidth(&self) -> Option<f64> {
+        schema::number(&self.attributes, "penwidth", Element::Node)
+    }
 
-    
+    pub fn fontsize(&self) -> Option<f64> {
+        schema::number(&self.attributes, "fontsize", Element::Node)
+    }
+
+    pub fn validate(&self) -> Vec<ValidationError> {
+        schema::validate(&self.attributes, Element::Node, &format!("node {}", self.identifier))
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{};", quote_id(&self.identifier), self.attributes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
 
     #[test]
     fn try_from_ok() {
         
         let mut first_map = HashMap::new();
-        first_map.insert("label".to_string(), "\"toto\"".to_string());
+        first_map.insert("label".to_string(), "toto".to_string());
         let mut second_map = HashMap::new();
-        second_map.insert("label".to_string(), "\"toto\"".to_string());
+        second_map.insert("label".to_string(), "toto".to_string());
         second_map.insert("encore".to_string(), "2".to_string());
         let combinations :Vec<(&str,Node)> = vec![
             ("A", Node::new("A", Attributs::default())),
@@ -77,4 +114,40 @@ mod test {
     
         combinations.iter().for_each(|combinaisons| assert_eq!(Node::try_from(&combinaisons.0.to_string()).unwrap(), combinaisons.1));
     } 
-}
\ No newline at end of file
+
+    #[test]
+    fn try_from_ko() {
+        let combinations :Vec<&str> = vec![
+            "A[label=\"toto\"",
+            "A B",
+            "[label=toto]",
+            ];
+
+        combinations.iter().for_each(|combinaisons| assert!(Node::try_from(&combinaisons.to_string()).is_err()));
+    }
+
+    #[test]
+    fn typed_getters() {
+        let node = Node::try_from(&"A[shape=box, style=\"filled, rounded\", width=2]".to_string()).unwrap();
+        assert_eq!(node.shape(), Some("box"));
+        assert_eq!(node.style(), vec!["filled", "rounded"]);
+        assert_eq!(node.width(), Some(2.0));
+        assert_eq!(node.height(), Some(0.5));
+        assert_eq!(node.color(), Some("black"));
+        assert_eq!(node.fillcolor(), None);
+        assert!(node.validate().is_empty());
+
+        let node = Node::try_from(&"A[shape=blob, weight=2]".to_string()).unwrap();
+        assert_eq!(node.shape(), Some("blob"));
+        assert_eq!(node.validate().len(), 2);
+    }
+
+    #[test]
+    fn try_from_html_label() {
+        let node = Node::try_from(&"Component[shape=none, label=<<table><tr><td>a, b=[c]</td></tr></table>>]".to_string()).unwrap();
+        let label = node.attributes.get_value("label").unwrap();
+        assert!(label.is_html());
+        assert_eq!(label.value(), "<table><tr><td>a, b=[c]</td></tr></table>");
+        assert_eq!(node.attributes.get("shape"), Some(&"none".to_string()));
+    }
+}
@@ -0,0 +1,615 @@
+use log::debug;
+
+use super::{attribut::Attribut, attribut_value::AttributValue, attributs::Attributs, dot_graph::DotGraph, edge::Edge, endpoint::{CompassPoint, Endpoint}, graph_type::GraphType, lexer::{tokenize, tokenize_recovering, Token, TokenKind}, node::Node, parsing_error::ParsingError, span::{LineIndex, Span}, type_relation::TypeRelation};
+
+enum Operand {
+    Node(Endpoint),
+    Graph(Vec<String>)
+}
+
+impl Operand {
+    fn endpoints(&self) -> Vec<Endpoint> {
+        match self {
+            Operand::Node(endpoint) => vec![endpoint.clone()],
+            Operand::Graph(identifiers) => identifiers.iter().map(|identifier| Endpoint::from(identifier.as_str())).collect()
+        }
+    }
+}
+
+// Recursive descent parser following the DOT grammar:
+// https://graphviz.org/doc/info/lang.html
+pub struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    end: Span,
+    // When set, statement errors are collected here instead of stopping the parse
+    errors: Option<Vec<ParsingError>>
+}
+
+impl Parser {
+    pub fn new(content: &str) -> Result<Self, ParsingError> {
+        Ok(Parser { tokens: tokenize(content)?, position: 0, end: LineIndex::new(content).end(), errors: None })
+    }
+
+    pub fn new_recovering(content: &str) -> Self {
+        let (tokens, errors) = tokenize_recovering(content);
+        Parser { tokens, position: 0, end: LineIndex::new(content).end(), errors: Some(errors) }
+    }
+
+    // Parse as much as possible, returning the partial graph with every error found
+    pub fn parse_graph_recovering(mut self) -> (DotGraph, Vec<ParsingError>) {
+        let graph = self.parse_graph();
+        let mut errors = self.errors.take().unwrap_or_default();
+        let graph = graph.unwrap_or_else(|error| {
+            errors.push(error);
+            DotGraph::default()
+        });
+        errors.sort_by_key(|error| error.span().map(|span| span.offset));
+        (graph, errors)
+    }
+
+    // graph : [ strict ] (graph | digraph) [ ID ] '{' stmt_list '}'
+    pub fn parse_graph(&mut self) -> Result<DotGraph, ParsingError> {
+        let strict = self.next_if(&TokenKind::Strict);
+
+        let family = match self.parse_graph_type() {
+            Ok(family) => family,
+            Err(error) => {
+                self.record(error)?;
+                self.skip_to_body();
+                self.guess_graph_type()
+            }
+        };
+
+        let name = self.parse_optional_id();
+        debug!("Parsing graph {}", name.as_deref().unwrap_or_default());
+
+        let mut graph = self.parse_block(family, name)?;
+        graph.set_strict(strict);
+        if let Err(error) = self.expect_end() {
+            self.record(error)?;
+        }
+        Ok(graph)
+    }
+
+    fn parse_graph_type(&mut self) -> Result<GraphType, ParsingError> {
+        match self.tokens.get(self.position) {
+            Some(Token{kind: TokenKind::Graph, ..}) => Ok(GraphType::Graph),
+            Some(Token{kind: TokenKind::Digraph, ..}) => Ok(GraphType::Digraph),
+            Some(other) => Err(ParsingError::MissingGraphKeyword { span: other.span, token: other.kind.text() }),
+            None => Err(ParsingError::UnexpectedEnd { span: self.end, expected: "'graph' or 'digraph'".to_string() })
+        }.inspect(|_| self.position += 1)
+    }
+
+    fn skip_to_body(&mut self) {
+        while !matches!(self.peek(), Some(TokenKind::LeftBrace) | None) {
+            self.position += 1;
+        }
+    }
+
+    // Without keyword, a digraph is assumed as soon as a '->' is used
+    fn guess_graph_type(&self) -> GraphType {
+        match self.tokens.iter().any(|token| token.kind == TokenKind::EdgeOp(TypeRelation::Oriente)) {
+            true => GraphType::Digraph,
+            false => GraphType::Graph
+        }
+    }
+
+    // Keep the error and skip the faulty statement when recovering, fail otherwise
+    fn recover(&mut self, error: ParsingError) -> Result<(), ParsingError> {
+        self.record(error)?;
+        self.synchronize();
+        Ok(())
+    }
+
+    fn record(&mut self, error: ParsingError) -> Result<(), ParsingError> {
+        let Some(errors) = self.errors.as_mut() else {
+            return Err(error);
+        };
+        debug!("Recovering from: {}", error);
+        errors.push(error);
+        Ok(())
+    }
+
+    // Skip to the next ';', the closing '}' of the current block or the next line
+    fn synchronize(&mut self) {
+        let line = self.tokens.get(self.position).map(|token| token.span.line);
+        let mut depth = 0;
+        while let Some(token) = self.tokens.get(self.position) {
+            match token.kind {
+                TokenKind::Semicolon if depth == 0 => {
+                    self.position += 1;
+                    return;
+                },
+                TokenKind::RightBrace if depth == 0 => return,
+                TokenKind::RightBrace => depth -= 1,
+                TokenKind::LeftBrace => depth += 1,
+                _ if depth == 0 && Some(token.span.line) != line => return,
+                _ => {}
+            }
+            self.position += 1;
+        }
+    }
+
+    // stmt : node_stmt | edge_stmt | attr_stmt | ID '=' ID | subgraph
+    fn parse_block(&mut self, family: GraphType, name: Option<String>) -> Result<DotGraph, ParsingError> {
+        let opening = self.expect(&TokenKind::LeftBrace)?;
+
+        let mut graph = DotGraph::new(family, vec![], vec![], vec![], Attributs::default(), name);
+
+        loop {
+            match self.peek() {
+                Some(TokenKind::RightBrace) => {
+                    self.next();
+                    break;
+                },
+                Some(_) => {
+                    if let Err(error) = self.parse_statement(&mut graph, family) {
+                        self.recover(error)?;
+                    }
+                },
+                None => {
+                    self.recover(ParsingError::UnbalancedBrace { span: opening.span, token: opening.kind.text() })?;
+                    break;
+                }
+            }
+        }
+
+        Ok(graph)
+    }
+
+    fn parse_statement(&mut self, graph: &mut DotGraph, family: GraphType) -> Result<(), ParsingError> {
+        match self.peek() {
+            Some(TokenKind::Semicolon) => {
+                self.next();
+            },
+            Some(TokenKind::Node) => {
+                self.next();
+                let attributs = self.parse_attr_list()?;
+                graph.push_node_defaults(attributs);
+            },
+            Some(TokenKind::Edge) => {
+                self.next();
+                let attributs = self.parse_attr_list()?;
+                graph.push_edge_defaults(attributs);
+            },
+            Some(TokenKind::Graph) => {
+                self.next();
+                let attributs = self.parse_attr_list()?;
+                graph.push_graph_attributs(attributs);
+            },
+            Some(TokenKind::Subgraph) | Some(TokenKind::LeftBrace) => {
+                self.parse_node_or_edge(graph, family)?;
+            },
+            Some(kind) if kind.is_id() => {
+                if self.peek_at(1) == Some(&TokenKind::Equal) {
+                    let attribut = self.parse_key_value()?;
+                    graph.push_graph_attributs(Attributs::from_iter([attribut]));
+                } else {
+                    self.parse_node_or_edge(graph, family)?;
+                }
+            },
+            _ => return Err(self.unexpected("a statement"))
+        }
+        Ok(())
+    }
+
+    // subgraph : [ subgraph [ ID ] ] '{' stmt_list '}'
+    fn parse_subgraph(&mut self, family: GraphType) -> Result<DotGraph, ParsingError> {
+        let name = match self.next_if(&TokenKind::Subgraph) {
+            true => self.parse_optional_id(),
+            false => None
+        };
+        self.parse_block(family, name)
+    }
+
+    // node_stmt : node_id [ attr_list ]
+    // edge_stmt : (node_id | subgraph) edgeRHS [ attr_list ]
+    // edgeRHS : edgeop (node_id | subgraph) [ edgeRHS ]
+    fn parse_node_or_edge(&mut self, graph: &mut DotGraph, family: GraphType) -> Result<(), ParsingError> {
+        let first = self.parse_edge_operand(graph, family)?;
+
+        if !matches!(self.peek(), Some(TokenKind::EdgeOp(_))) {
+            // Ports have no meaning on a node statement
+            if let Operand::Node(endpoint) = first {
+                let attributes = self.parse_optional_attr_list()?;
+                graph.push_node(Node::new(&endpoint.identifier, attributes));
+            }
+            return Ok(());
+        }
+
+        let mut operands = vec![first];
+        while matches!(self.peek(), Some(TokenKind::EdgeOp(_))) {
+            self.expect_edge_op(family)?;
+            operands.push(self.parse_edge_operand(graph, family)?);
+        }
+
+        // Every hop of a chain shares the attribute list, subgraphs stand for all their nodes
+        let attributs = self.parse_optional_attr_list()?;
+        operands.windows(2)
+            .flat_map(|pair| pair[0].endpoints().into_iter()
+                .flat_map(|node_out| pair[1].endpoints().into_iter().map(move |node_in| (node_out.clone(), node_in))))
+            .map(|(node_out, node_in)| Edge{node_out, node_in, relation: TypeRelation::from(family), attributs: attributs.clone()})
+            .for_each(|edge| graph.push_edge(edge));
+        Ok(())
+    }
+
+    // Subgraph operands are kept in the enclosing graph
+    fn parse_edge_operand(&mut self, graph: &mut DotGraph, family: GraphType) -> Result<Operand, ParsingError> {
+        match self.peek() {
+            Some(TokenKind::Subgraph) | Some(TokenKind::LeftBrace) => {
+                let sous_graphe = self.parse_subgraph(family)?;
+                let identifiers = sous_graphe.node_identifiers();
+                graph.push_sous_graphe(sous_graphe);
+                Ok(Operand::Graph(identifiers))
+            },
+            _ => self.parse_node_id().map(Operand::Node)
+        }
+    }
+
+    // Standalone node statement, used by Node::try_from
+    pub fn parse_node_statement(&mut self) -> Result<Node, ParsingError> {
+        let endpoint = self.parse_node_id()?;
+        let attributes = self.parse_optional_attr_list()?;
+        self.expect_end()?;
+        Ok(Node::new(&endpoint.identifier, attributes))
+    }
+
+    // Standalone edge statement, used by Edge::try_from
+    pub fn parse_edge_statement(&mut self, relation: TypeRelation) -> Result<Edge, ParsingError> {
+        let node_out = self.parse_node_id()?;
+        self.expect(&TokenKind::EdgeOp(relation.clone()))?;
+        let node_in = self.parse_node_id()?;
+        let attributs = self.parse_optional_attr_list()?;
+        self.expect_end()?;
+        Ok(Edge{node_out, node_in, relation, attributs})
+    }
+
+    // Standalone attribute list content, used by Attributs::try_from
+    pub fn parse_attributs(&mut self) -> Result<Attributs, ParsingError> {
+        let attributs = self.parse_a_list()?;
+        self.expect_end()?;
+        Ok(attributs)
+    }
+
+    // Standalone ID '=' ID, used by Attribut::try_from
+    pub fn parse_attribut(&mut self) -> Result<Attribut, ParsingError> {
+        let attribut = self.parse_key_value()?;
+        self.expect_end()?;
+        Ok(attribut)
+    }
+
+    // node_id : ID [ port ]
+    // port : ':' ID [ ':' compass_pt ] | ':' compass_pt
+    // A lone port named like a compass point is a compass point
+    fn parse_node_id(&mut self) -> Result<Endpoint, ParsingError> {
+        let identifier = self.parse_id()?;
+        if !self.next_if(&TokenKind::Colon) {
+            return Ok(Endpoint::from(identifier));
+        }
+
+        let port = self.parse_id()?;
+        if self.next_if(&TokenKind::Colon) {
+            let compass = self.parse_compass_point()?;
+            return Ok(Endpoint { identifier, port: Some(port), compass: Some(compass) });
+        }
+
+        Ok(match CompassPoint::try_from(port.as_str()) {
+            Ok(compass) => Endpoint { identifier, port: None, compass: Some(compass) },
+            Err(_) => Endpoint { identifier, port: Some(port), compass: None }
+        })
+    }
+
+    fn parse_compass_point(&mut self) -> Result<CompassPoint, ParsingError> {
+        match self.peek() {
+            Some(TokenKind::Id(text)) => CompassPoint::try_from(text.as_str()).ok(),
+            _ => None
+        }
+        .inspect(|_| self.position += 1)
+        .ok_or_else(|| self.unexpected("a compass point"))
+    }
+
+    fn parse_optional_attr_list(&mut self) -> Result<Attributs, ParsingError> {
+        match self.peek() {
+            Some(TokenKind::LeftBracket) => self.parse_attr_list(),
+            _ => Ok(Attributs::default())
+        }
+    }
+
+    // attr_list : '[' [ a_list ] ']' [ attr_list ]
+    fn parse_attr_list(&mut self) -> Result<Attributs, ParsingError> {
+        let mut attributs = Attributs::default();
+        loop {
+            self.expect(&TokenKind::LeftBracket)?;
+            if !self.next_if(&TokenKind::RightBracket) {
+                attributs.extend(&self.parse_a_list()?);
+                self.expect(&TokenKind::RightBracket)?;
+            }
+            if self.peek() != Some(&TokenKind::LeftBracket) {
+                return Ok(attributs);
+            }
+        }
+    }
+
+    // a_list : ID '=' ID [ (';' | ',') ] [ a_list ]
+    fn parse_a_list(&mut self) -> Result<Attributs, ParsingError> {
+        let mut attributs = vec![];
+        while self.peek().is_some_and(TokenKind::is_id) {
+            attributs.push(self.parse_key_value()?);
+            if !self.next_if(&TokenKind::Comma) {
+                self.next_if(&TokenKind::Semicolon);
+            }
+        }
+        Ok(Attributs::from_iter(attributs))
+    }
+
+    fn parse_key_value(&mut self) -> Result<Attribut, ParsingError> {
+        let key = self.parse_id()?;
+        self.expect(&TokenKind::Equal)?;
+        let value = self.parse_value()?;
+        Ok(Attribut{key, value})
+    }
+
+    fn parse_value(&mut self) -> Result<AttributValue, ParsingError> {
+        match self.peek() {
+            Some(TokenKind::QuotedString { raw, value }) => Ok(AttributValue::Quoted { raw: raw.clone(), value: value.clone() }),
+            Some(kind @ TokenKind::HtmlString(_)) => Ok(AttributValue::Html(kind.value())),
+            Some(TokenKind::Numeral(text)) => Ok(AttributValue::Numeral(text.clone())),
+            Some(kind) if kind.is_id() => Ok(AttributValue::Id(kind.text())),
+            _ => Err(self.unexpected("a value"))
+        }.inspect(|_| self.position += 1)
+    }
+
+    // Graph and subgraph names, any ID form
+    fn parse_optional_id(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(kind) if kind.is_id() => self.next().map(|token| token.kind.value()),
+            _ => None
+        }
+    }
+
+    // Node identifiers, ports and attribute keys, decoded
+    fn parse_id(&mut self) -> Result<String, ParsingError> {
+        match self.peek() {
+            Some(kind) if kind.is_id() => Ok(kind.value()),
+            _ => Err(self.unexpected("an identifier"))
+        }.inspect(|_| self.position += 1)
+    }
+
+    fn expect_edge_op(&mut self, family: GraphType) -> Result<Token, ParsingError> {
+        let expected = TypeRelation::from(family);
+        match self.tokens.get(self.position) {
+            Some(token) if token.kind == TokenKind::EdgeOp(expected) => Ok(token.clone()),
+            Some(token) => Err(ParsingError::MismatchedEdgeOperator { span: token.span, token: token.kind.text(), family: family.to_string() }),
+            None => Err(ParsingError::UnexpectedEnd { span: self.end, expected: "an edge operator".to_string() })
+        }.inspect(|_| self.position += 1)
+    }
+
+    fn expect_end(&self) -> Result<(), ParsingError> {
+        match self.tokens.get(self.position) {
+            None => Ok(()),
+            Some(token) if token.kind == TokenKind::RightBrace => Err(ParsingError::UnbalancedBrace { span: token.span, token: token.kind.text() }),
+            Some(_) => Err(self.unexpected("end of content"))
+        }
+    }
+
+    fn expect(&mut self, expected: &TokenKind) -> Result<Token, ParsingError> {
+        match self.tokens.get(self.position) {
+            Some(token) if token.kind == *expected => Ok(token.clone()),
+            _ => Err(self.unexpected(&format!("'{}'", expected.text())))
+        }.inspect(|_| self.position += 1)
+    }
+
+    // Error on the current token
+    fn unexpected(&self, expected: &str) -> ParsingError {
+        match self.tokens.get(self.position) {
+            Some(token) => ParsingError::UnexpectedToken { span: token.span, token: token.kind.text(), expected: expected.to_string() },
+            None => ParsingError::UnexpectedEnd { span: self.end, expected: expected.to_string() }
+        }
+    }
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&TokenKind> {
+        self.tokens.get(self.position + offset).map(|token| &token.kind)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        if token.is_some() {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn next_if(&mut self, expected: &TokenKind) -> bool {
+        let matching = self.peek() == Some(expected);
+        if matching {
+            self.position += 1;
+        }
+        matching
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Result<DotGraph, ParsingError> {
+        Parser::new(content)?.parse_graph()
+    }
+
+    #[test]
+    fn parse_special_characters_in_strings() {
+        let graph = parse("digraph G { A [label=\"a; b -> c {x}\"]; A -> B [label=\"x=1, y=[2]\"]; }").unwrap();
+        assert_eq!(graph.nodes().len(), 1);
+        assert_eq!(graph.nodes()[0].attributes.label(), Some(&"a; b -> c {x}".to_string()));
+        assert_eq!(graph.edges().len(), 1);
+        assert_eq!(graph.edges()[0].attributs.label(), Some(&"x=1, y=[2]".to_string()));
+    }
+
+    #[test]
+    fn parse_quoted_identifiers() {
+        let graph = parse("digraph \"My Graph\" { \"node {1}\" -> \"node;2\" }").unwrap();
+        assert_eq!(graph.name(), Some("My Graph"));
+        assert_eq!(graph.edges()[0].node_out, Endpoint::from("node {1}"));
+        assert_eq!(graph.edges()[0].node_in, Endpoint::from("node;2"));
+    }
+
+    #[test]
+    fn parse_graph_ids() {
+        let combinations :Vec<(&str, Option<&str>)> = vec![
+            ("digraph{}", None),
+            ("digraph G{}", Some("G")),
+            ("digraph 42 {}", Some("42")),
+            ("digraph \"My \\\"big\\\" graph\" {}", Some("My \"big\" graph")),
+            ("digraph <G<sub>1</sub>> {}", Some("G<sub>1</sub>")),
+            ];
+
+        combinations.iter().for_each(|combinaisons| assert_eq!(parse(combinaisons.0).unwrap().name(), combinaisons.1));
+    }
+
+    #[test]
+    fn parse_keywords_ignore_case() {
+        let graph = parse("STRICT DiGraph G { Node [shape=box]; EDGE [color=red]; A -> B; SUBGRAPH \"cluster a\" { C } }").unwrap();
+
+        assert!(graph.is_strict());
+        assert_eq!(graph.edges().len(), 1);
+        assert_eq!(graph.nodes().len(), 1);
+        assert_eq!(graph.sous_graphes()[0].name(), Some("cluster a"));
+    }
+
+    #[test]
+    fn parse_graph_without_name() {
+        let graph = parse("graph{A -- B}").unwrap();
+        assert_eq!(graph.name(), None);
+        assert_eq!(graph.edges()[0].relation, TypeRelation::NonOriente);
+    }
+
+    #[test]
+    fn parse_edge_chains() {
+        let graph = parse("digraph G { A -> B -> C -> D [color=red]; D -> A }").unwrap();
+        let edges = graph.edges();
+
+        assert_eq!(edges.iter().map(|edge| format!("{} {}", edge.node_out, edge.node_in)).collect::<Vec<_>>(),
+            vec!["A B", "B C", "C D", "D A"]);
+        assert!(edges[..3].iter().all(|edge| edge.attributs.get("color") == Some(&"red".to_string())));
+        assert_eq!(edges[3].attributs, Attributs::default());
+    }
+
+    #[test]
+    fn parse_undirected_edge_chains() {
+        let graph = parse("graph G { \"a b\" -- c:p -- d }").unwrap();
+
+        assert_eq!(graph.edges().iter().map(|edge| format!("{} {}", edge.node_out, edge.node_in)).collect::<Vec<_>>(),
+            vec!["\"a b\" c:p", "c:p d"]);
+    }
+
+    #[test]
+    fn parse_subgraph_operands() {
+        let graph = parse("digraph G { A -> {B C} -> subgraph s { D; E -> F } [color=red]; {G} -> H }").unwrap();
+
+        assert_eq!(graph.edges().iter().filter(|edge| edge.attributs.get("color").is_some()).map(|edge| format!("{} {}", edge.node_out, edge.node_in)).collect::<Vec<_>>(),
+            vec!["A B", "A C", "B D", "B E", "B F", "C D", "C E", "C F"]);
+        assert_eq!(graph.edges().iter().filter(|edge| edge.attributs.get("color").is_none()).map(|edge| format!("{} {}", edge.node_out, edge.node_in)).collect::<Vec<_>>(),
+            vec!["G H", "E F"]);
+        assert_eq!(graph.sous_graphes().iter().map(|sous_graphe| sous_graphe.name()).collect::<Vec<_>>(), vec![None, Some("s"), None]);
+    }
+
+    #[test]
+    fn parse_undirected_subgraph_operands() {
+        let graph = parse("graph G { {A B} -- {C subgraph { D }} }").unwrap();
+
+        assert_eq!(graph.edges().iter().map(|edge| format!("{} {}", edge.node_out, edge.node_in)).collect::<Vec<_>>(),
+            vec!["A C", "A D", "B C", "B D"]);
+    }
+
+    #[test]
+    fn parse_attribute_lists() {
+        let graph = parse("digraph G { A [color=red][shape=box; label=a,][]; A -> B [][style=bold;] }").unwrap();
+
+        assert_eq!(graph.nodes()[0].attributes, Attributs::try_from(&"color=red, shape=box, label=a".to_string()).unwrap());
+        assert_eq!(graph.edges()[0].attributs, Attributs::try_from(&"style=bold".to_string()).unwrap());
+    }
+
+    #[test]
+    fn parse_graph_attributes() {
+        let graph = parse("digraph G { graph [rankdir=LR, splines=ortho]; A; label=\"G\"; GRAPH [rankdir=TB] }").unwrap();
+
+        assert_eq!(graph.nodes().len(), 1);
+        assert_eq!(graph.attributs(), &Attributs::try_from(&"rankdir=TB, splines=ortho, label=\"G\"".to_string()).unwrap());
+    }
+
+    #[test]
+    fn parse_ko_positions() {
+        let combinations :Vec<(&str, (usize, usize), Option<&str>)> = vec![
+            ("A -> B", (1, 1), Some("A")),
+            ("", (1, 1), None),
+            ("digraph G {\n  A -> B", (1, 11), Some("{")),
+            ("digraph G { A -> B }}", (1, 21), Some("}")),
+            ("digraph G {\n  A -- B }", (2, 5), Some("--")),
+            ("graph G { A -> B }", (1, 13), Some("->")),
+            ("digraph G {\n\tA [label=] }", (2, 11), Some("]")),
+            ("digraph G { A -> }", (1, 18), Some("}")),
+            ("digraph G { A -> B -- C }", (1, 20), Some("--")),
+            ];
+
+        combinations.iter().for_each(|combinaisons| {
+            let error = parse(combinaisons.0).unwrap_err();
+            assert_eq!(error.span().map(|span| (span.line, span.column)), Some(combinaisons.1), "{}", combinaisons.0);
+            assert_eq!(error.token(), combinaisons.2, "{}", combinaisons.0);
+        });
+    }
+
+    #[test]
+    fn parse_recovering_collects_every_error() {
+        let content = "digraph G {\n  A -> B;\n  A -- C;\n  D [label=];\n  E $ F;\n  subgraph s { G -> ; H }\n  I -> J\n}";
+        let (graph, errors) = Parser::new_recovering(content).parse_graph_recovering();
+
+        assert_eq!(errors.iter().map(|error| error.span().map(|span| span.line).unwrap()).collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+        assert!(matches!(errors[0], ParsingError::MismatchedEdgeOperator { .. }));
+        assert!(matches!(errors[2], ParsingError::UnexpectedCharacter { .. }));
+        assert_eq!(graph.edges().iter().map(|edge| edge.node_out.to_string() + &edge.node_in.to_string()).collect::<Vec<_>>(), vec!["AB", "IJ"]);
+        assert_eq!(graph.nodes().iter().map(|node| node.identifier.clone()).collect::<Vec<_>>(), vec!["E", "F", "H"]);
+    }
+
+    #[test]
+    fn parse_recovering_without_newlines() {
+        let (graph, errors) = Parser::new_recovering("graph G { A -- ; B -- C; ] D ; E }").parse_graph_recovering();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(graph.edges().len(), 1);
+        assert_eq!(graph.nodes().len(), 1);
+    }
+
+    #[test]
+    fn parse_recovering_broken_header_and_braces() {
+        let (graph, errors) = Parser::new_recovering("G { A -> B ").parse_graph_recovering();
+
+        assert!(matches!(errors[0], ParsingError::MissingGraphKeyword { .. }));
+        assert!(matches!(errors[1], ParsingError::UnbalancedBrace { .. }));
+        assert_eq!(graph.edges().len(), 1);
+        assert_eq!(graph.edges()[0].relation, TypeRelation::Oriente);
+    }
+
+    #[test]
+    fn parse_recovering_ok_has_no_error() {
+        let (graph, errors) = Parser::new_recovering("digraph G { A -> B }").parse_graph_recovering();
+
+        assert!(errors.is_empty());
+        assert_eq!(graph, parse("digraph G { A -> B }").unwrap());
+    }
+
+    #[test]
+    fn parse_ko_variants() {
+        assert!(matches!(parse("A -> B").unwrap_err(), ParsingError::MissingGraphKeyword { .. }));
+        assert!(matches!(parse("digraph G { A -> B").unwrap_err(), ParsingError::UnbalancedBrace { .. }));
+        assert!(matches!(parse("digraph G { A -> B }}").unwrap_err(), ParsingError::UnbalancedBrace { .. }));
+        assert!(matches!(parse("digraph G { A -- B }").unwrap_err(), ParsingError::MismatchedEdgeOperator { .. }));
+        assert!(matches!(parse("digraph G { A [label=\"x] }").unwrap_err(), ParsingError::UnterminatedString { .. }));
+        assert!(matches!(parse("digraph G { A [label=] }").unwrap_err(), ParsingError::UnexpectedToken { .. }));
+    }
+}
@@ -1,11 +1,84 @@
 use thiserror::Error;
 
+use super::span::Span;
+
 
 #[derive(Error, Debug)]
 pub enum ParsingError
 {
     #[error("An error occured: {0}")]
     DefaultError(String),
+    #[error("Unexpected character '{token}' at {span}")]
+    UnexpectedCharacter { span: Span, token: String },
+    #[error("Unterminated string starting at {span}")]
+    UnterminatedString { span: Span, token: String },
+    #[error("Unterminated HTML string starting at {span}")]
+    UnterminatedHtmlString { span: Span, token: String },
+    #[error("Unterminated comment starting at {span}")]
+    UnterminatedComment { span: Span, token: String },
+    #[error("Invalid numeral '{token}' at {span}")]
+    InvalidNumeral { span: Span, token: String },
+    #[error("Unbalanced brace '{token}' at {span}")]
+    UnbalancedBrace { span: Span, token: String },
+    #[error("Unknown edge operator '{token}' at {span}")]
+    UnknownEdgeOperator { span: Span, token: String },
+    #[error("Edge operator '{token}' is not allowed in a {family} at {span}")]
+    MismatchedEdgeOperator { span: Span, token: String, family: String },
+    #[error("Missing graph keyword, found '{token}' at {span}")]
+    MissingGraphKeyword { span: Span, token: String },
+    #[error("Expected {expected}, found '{token}' at {span}")]
+    UnexpectedToken { span: Span, token: String, expected: String },
+    #[error("Expected {expected}, found end of content at {span}")]
+    UnexpectedEnd { span: Span, expected: String },
+    #[error("{path}:{}:{}: {error}", error.span().map(|span| span.line).unwrap_or_default(), error.span().map(|span| span.column).unwrap_or_default())]
+    InFile { path: String, error: Box<ParsingError> },
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
+
+impl ParsingError {
+    // Where the error happened in the parsed content, if known
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            ParsingError::UnexpectedCharacter { span, .. }
+            | ParsingError::UnterminatedString { span, .. }
+            | ParsingError::UnterminatedHtmlString { span, .. }
+            | ParsingError::UnterminatedComment { span, .. }
+            | ParsingError::InvalidNumeral { span, .. }
+            | ParsingError::UnbalancedBrace { span, .. }
+            | ParsingError::UnknownEdgeOperator { span, .. }
+            | ParsingError::MismatchedEdgeOperator { span, .. }
+            | ParsingError::MissingGraphKeyword { span, .. }
+            | ParsingError::UnexpectedToken { span, .. }
+            | ParsingError::UnexpectedEnd { span, .. } => Some(span),
+            ParsingError::InFile { error, .. } => error.span(),
+            ParsingError::DefaultError(_) | ParsingError::Other(_) => None,
+        }
+    }
+
+    // Offending token as found in the parsed content, if any
+    pub fn token(&self) -> Option<&str> {
+        match self {
+            ParsingError::UnexpectedCharacter { token, .. }
+            | ParsingError::UnterminatedString { token, .. }
+            | ParsingError::UnterminatedHtmlString { token, .. }
+            | ParsingError::UnterminatedComment { token, .. }
+            | ParsingError::InvalidNumeral { token, .. }
+            | ParsingError::UnbalancedBrace { token, .. }
+            | ParsingError::UnknownEdgeOperator { token, .. }
+            | ParsingError::MismatchedEdgeOperator { token, .. }
+            | ParsingError::MissingGraphKeyword { token, .. }
+            | ParsingError::UnexpectedToken { token, .. } => Some(token),
+            ParsingError::InFile { error, .. } => error.token(),
+            ParsingError::UnexpectedEnd { .. } | ParsingError::DefaultError(_) | ParsingError::Other(_) => None,
+        }
+    }
+
+    // File the parsed content came from, if any
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            ParsingError::InFile { path, .. } => Some(path),
+            _ => None
+        }
+    }
+}
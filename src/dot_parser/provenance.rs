@@ -0,0 +1,131 @@
+use std::fmt::Display;
+
+use super::{attribut_value::AttributValue, attributs::Attributs, schema::{Element, SCHEMA}};
+
+// Where the effective value of an attribute comes from
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Provenance {
+    // Set on the node or edge statement itself
+    Statement,
+    // node [ ... ] or edge [ ... ] statement of the root graph
+    GraphDefault,
+    // node [ ... ] or edge [ ... ] statement of a subgraph, None when anonymous
+    SousGrapheDefault(Option<String>),
+    // Graphviz default, as listed in the attribute catalogue
+    BuiltIn,
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Provenance::Statement => write!(f, "statement"),
+            Provenance::GraphDefault => write!(f, "default of the graph"),
+            Provenance::SousGrapheDefault(Some(name)) => write!(f, "default in subgraph {}", name),
+            Provenance::SousGrapheDefault(None) => write!(f, "default in an anonymous subgraph"),
+            Provenance::BuiltIn => write!(f, "Graphviz default"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct EffectiveAttribut {
+    pub value: AttributValue,
+    pub provenance: Provenance,
+}
+
+// Effective attributes of a node or an edge, each with where its value comes from
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct EffectiveAttributs(Vec<(String, EffectiveAttribut)>);
+
+impl EffectiveAttributs {
+    // Graphviz defaults of every catalogued attribute of the element
+    pub(crate) fn built_in(element: Element) -> Self {
+        let mut effective = EffectiveAttributs::default();
+        SCHEMA.iter()
+            .filter_map(|schema| schema.default_for(element).map(|default| (schema.name, default)))
+            .for_each(|(name, default)| effective.set(name, AttributValue::from(default), Provenance::BuiltIn));
+        effective
+    }
+
+    pub fn get(&self, key: &str) -> Option<&EffectiveAttribut> {
+        self.0.iter().find(|(id, _)| id == key).map(|(_, effective)| effective)
+    }
+
+    pub fn value(&self, key: &str) -> Option<&AttributValue> {
+        self.get(key).map(|effective| &effective.value)
+    }
+
+    pub fn provenance(&self, key: &str) -> Option<&Provenance> {
+        self.get(key).map(|effective| &effective.provenance)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &EffectiveAttribut)> {
+        self.0.iter().map(|(key, effective)| (key, effective))
+    }
+
+    // Values only, as they would be given to Graphviz
+    pub fn attributs(&self) -> Attributs {
+        let mut attributs = Attributs::default();
+        self.0.iter().for_each(|(key, effective)| attributs.set(key, effective.value.clone()));
+        attributs
+    }
+
+    // Add the attributes, overriding the ones already set in place
+    pub(crate) fn extend(&mut self, attributs: &Attributs, provenance: &Provenance) {
+        attributs.iter().for_each(|(key, value)| self.set(key, value.clone(), provenance.clone()));
+    }
+
+    pub(crate) fn merge(&mut self, other: &EffectiveAttributs) {
+        other.0.iter().for_each(|(key, effective)| self.set(key, effective.value.clone(), effective.provenance.clone()));
+    }
+
+    fn set(&mut self, key: &str, value: AttributValue, provenance: Provenance) {
+        let effective = EffectiveAttribut { value, provenance };
+        match self.0.iter_mut().find(|(id, _)| id == key) {
+            Some((_, current)) => *current = effective,
+            None => self.0.push((key.to_string(), effective))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extend_overrides_provenance() {
+        let mut effective = EffectiveAttributs::default();
+        effective.extend(&Attributs::try_from(&"color=red, shape=box".to_string()).unwrap(), &Provenance::GraphDefault);
+        effective.extend(&Attributs::try_from(&"color=blue".to_string()).unwrap(), &Provenance::Statement);
+
+        assert_eq!(effective.provenance("color"), Some(&Provenance::Statement));
+        assert_eq!(effective.provenance("shape"), Some(&Provenance::GraphDefault));
+        assert_eq!(effective.provenance("label"), None);
+        assert_eq!(effective.attributs().to_string(), "[color=blue,shape=box]");
+    }
+
+    #[test]
+    fn provenance_display() {
+        let combinations = vec![
+            (Provenance::Statement, "statement"),
+            (Provenance::GraphDefault, "default of the graph"),
+            (Provenance::SousGrapheDefault(Some("cluster_a".to_string())), "default in subgraph cluster_a"),
+            (Provenance::SousGrapheDefault(None), "default in an anonymous subgraph"),
+            (Provenance::BuiltIn, "Graphviz default"),
+        ];
+
+        combinations.into_iter().for_each(|combinaisons| assert_eq!(combinaisons.0.to_string(), combinaisons.1));
+    }
+}
@@ -0,0 +1,371 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use super::{attribut_value::AttributValue, attributs::Attributs};
+
+// Kind of element an attribute can be set on
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Element {
+    Graph,
+    Subgraph,
+    Cluster,
+    Node,
+    Edge,
+}
+
+// Type of the values an attribute accepts
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ValueType {
+    String,
+    // Plain or HTML-like string
+    Label,
+    Double,
+    Int,
+    Bool,
+    // Color or list of colors separated by ':'
+    Color,
+    // "x,y" with an optional third coordinate and '!'
+    Point,
+    // Comma separated styles
+    Style,
+    Arrow,
+    Enum(&'static [&'static str]),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct AttributSchema {
+    pub name: &'static str,
+    pub value_type: ValueType,
+    pub usage: &'static [Element],
+    // Graphviz default, when it does not depend on the layout nor the graph type
+    pub default: Option<&'static str>,
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum ValidationError {
+    #[error("Unknown attribute '{name}' on {element}")]
+    UnknownAttribut { element: String, name: String },
+    #[error("Attribute '{name}' on {element} expects {expected}, found '{value}'")]
+    InvalidValue { element: String, name: String, value: String, expected: String },
+    #[error("Attribute '{name}' does not apply to {element}")]
+    WrongElement { element: String, name: String },
+}
+
+const SHAPES: &[&str] = &["box", "polygon", "ellipse", "oval", "circle", "point", "egg", "triangle", "plaintext", "plain", "diamond",
+    "trapezium", "parallelogram", "house", "pentagon", "hexagon", "septagon", "octagon", "doublecircle", "doubleoctagon", "tripleoctagon",
+    "invtriangle", "invtrapezium", "invhouse", "mdiamond", "msquare", "mcircle", "rect", "rectangle", "square", "star", "none", "underline",
+    "cylinder", "note", "tab", "folder", "box3d", "component", "promoter", "cds", "terminator", "utr", "primersite", "restrictionsite",
+    "fivepoverhang", "threepoverhang", "noverhang", "assembly", "signature", "insulator", "ribosite", "rnastab", "proteasesite",
+    "proteinstab", "rpromoter", "rarrow", "larrow", "lpromoter", "record", "mrecord"];
+const STYLES: &[&str] = &["solid", "dashed", "dotted", "bold", "invis", "filled", "striped", "wedged", "diagonals", "rounded",
+    "tapered", "radial", "setlinewidth"];
+const ARROWS: &[&str] = &["box", "crow", "curve", "icurve", "diamond", "dot", "inv", "none", "normal", "tee", "vee"];
+const LEGACY_ARROWS: &[&str] = &["ediamond", "open", "halfopen", "empty", "invempty", "invdot", "invodot"];
+
+const ALL: &[Element] = &[Element::Graph, Element::Cluster, Element::Node, Element::Edge];
+const NODE: &[Element] = &[Element::Node];
+const EDGE: &[Element] = &[Element::Edge];
+const GRAPH: &[Element] = &[Element::Graph];
+const NODE_EDGE: &[Element] = &[Element::Node, Element::Edge];
+const NODE_EDGE_CLUSTER: &[Element] = &[Element::Node, Element::Edge, Element::Cluster];
+const GRAPH_CLUSTER: &[Element] = &[Element::Graph, Element::Cluster];
+const GRAPH_CLUSTER_NODE: &[Element] = &[Element::Graph, Element::Cluster, Element::Node];
+
+const fn attribut(name: &'static str, value_type: ValueType, usage: &'static [Element], default: Option<&'static str>) -> AttributSchema {
+    AttributSchema { name, value_type, usage, default }
+}
+
+// Attributes documented by Graphviz, for the dot layout
+pub const SCHEMA: &[AttributSchema] = &[
+    attribut("label", ValueType::Label, ALL, None),
+    attribut("xlabel", ValueType::Label, NODE_EDGE, None),
+    attribut("headlabel", ValueType::Label, EDGE, None),
+    attribut("taillabel", ValueType::Label, EDGE, None),
+    attribut("shape", ValueType::Enum(SHAPES), NODE, Some("ellipse")),
+    attribut("sides", ValueType::Int, NODE, Some("4")),
+    attribut("peripheries", ValueType::Int, &[Element::Node, Element::Cluster], None),
+    attribut("regular", ValueType::Bool, NODE, Some("false")),
+    attribut("width", ValueType::Double, NODE, Some("0.75")),
+    attribut("height", ValueType::Double, NODE, Some("0.5")),
+    attribut("fixedsize", ValueType::Enum(&["true", "false", "shape"]), NODE, Some("false")),
+    attribut("group", ValueType::String, NODE, None),
+    attribut("image", ValueType::String, NODE, None),
+    attribut("color", ValueType::Color, NODE_EDGE_CLUSTER, Some("black")),
+    attribut("fillcolor", ValueType::Color, NODE_EDGE_CLUSTER, None),
+    attribut("fontcolor", ValueType::Color, ALL, Some("black")),
+    attribut("bgcolor", ValueType::Color, GRAPH_CLUSTER, None),
+    attribut("pencolor", ValueType::Color, &[Element::Cluster], Some("black")),
+    attribut("colorscheme", ValueType::String, ALL, None),
+    attribut("fontname", ValueType::String, ALL, Some("Times-Roman")),
+    attribut("fontsize", ValueType::Double, ALL, Some("14")),
+    attribut("style", ValueType::Style, NODE_EDGE_CLUSTER, None),
+    attribut("penwidth", ValueType::Double, NODE_EDGE_CLUSTER, Some("1")),
+    attribut("gradientangle", ValueType::Int, GRAPH_CLUSTER_NODE, None),
+    attribut("margin", ValueType::Point, GRAPH_CLUSTER_NODE, None),
+    attribut("labelloc", ValueType::Enum(&["t", "c", "b"]), GRAPH_CLUSTER_NODE, None),
+    attribut("labeljust", ValueType::Enum(&["l", "r", "c"]), GRAPH_CLUSTER, Some("c")),
+    attribut("nojustify", ValueType::Bool, ALL, Some("false")),
+    attribut("tooltip", ValueType::String, ALL, None),
+    attribut("URL", ValueType::String, ALL, None),
+    attribut("href", ValueType::String, ALL, None),
+    attribut("target", ValueType::String, ALL, None),
+    attribut("id", ValueType::String, ALL, None),
+    attribut("class", ValueType::String, ALL, None),
+    attribut("comment", ValueType::String, ALL, None),
+    attribut("pos", ValueType::String, NODE_EDGE, None),
+    attribut("ordering", ValueType::Enum(&["in", "out", ""]), &[Element::Graph, Element::Node], None),
+    attribut("rank", ValueType::Enum(&["same", "min", "source", "max", "sink"]), &[Element::Subgraph], None),
+    attribut("rankdir", ValueType::Enum(&["TB", "LR", "BT", "RL"]), GRAPH, Some("TB")),
+    attribut("ranksep", ValueType::String, GRAPH, Some("0.5")),
+    attribut("nodesep", ValueType::Double, GRAPH, Some("0.25")),
+    attribut("newrank", ValueType::Bool, GRAPH, Some("false")),
+    attribut("compound", ValueType::Bool, GRAPH, Some("false")),
+    attribut("concentrate", ValueType::Bool, GRAPH, Some("false")),
+    attribut("clusterrank", ValueType::Enum(&["local", "global", "none"]), GRAPH, Some("local")),
+    attribut("splines", ValueType::Enum(&["true", "false", "yes", "no", "none", "line", "spline", "polyline", "ortho", "curved", "compound", ""]), GRAPH, None),
+    attribut("size", ValueType::Point, GRAPH, None),
+    attribut("ratio", ValueType::String, GRAPH, None),
+    attribut("pad", ValueType::Point, GRAPH, None),
+    attribut("dpi", ValueType::Double, GRAPH, Some("96")),
+    attribut("rotate", ValueType::Int, GRAPH, Some("0")),
+    attribut("landscape", ValueType::Bool, GRAPH, Some("false")),
+    attribut("center", ValueType::Bool, GRAPH, Some("false")),
+    attribut("layout", ValueType::String, GRAPH, None),
+    attribut("charset", ValueType::String, GRAPH, Some("UTF-8")),
+    attribut("outputorder", ValueType::Enum(&["breadthfirst", "nodesfirst", "edgesfirst"]), GRAPH, Some("breadthfirst")),
+    attribut("remincross", ValueType::Bool, GRAPH, None),
+    attribut("searchsize", ValueType::Int, GRAPH, Some("30")),
+    attribut("mclimit", ValueType::Double, GRAPH, Some("1")),
+    attribut("nslimit", ValueType::Double, GRAPH, None),
+    attribut("root", ValueType::String, &[Element::Graph, Element::Node], None),
+    attribut("dir", ValueType::Enum(&["forward", "back", "both", "none"]), EDGE, None),
+    attribut("arrowhead", ValueType::Arrow, EDGE, Some("normal")),
+    attribut("arrowtail", ValueType::Arrow, EDGE, Some("normal")),
+    attribut("arrowsize", ValueType::Double, EDGE, Some("1")),
+    attribut("weight", ValueType::Double, EDGE, Some("1")),
+    attribut("constraint", ValueType::Bool, EDGE, Some("true")),
+    attribut("minlen", ValueType::Int, EDGE, Some("1")),
+    attribut("headport", ValueType::String, EDGE, None),
+    attribut("tailport", ValueType::String, EDGE, None),
+    attribut("headclip", ValueType::Bool, EDGE, Some("true")),
+    attribut("tailclip", ValueType::Bool, EDGE, Some("true")),
+    attribut("lhead", ValueType::String, EDGE, None),
+    attribut("ltail", ValueType::String, EDGE, None),
+    attribut("samehead", ValueType::String, EDGE, None),
+    attribut("sametail", ValueType::String, EDGE, None),
+    attribut("decorate", ValueType::Bool, EDGE, Some("false")),
+    attribut("labelangle", ValueType::Double, EDGE, Some("-25")),
+    attribut("labeldistance", ValueType::Double, EDGE, Some("1")),
+    attribut("labelfloat", ValueType::Bool, EDGE, Some("false")),
+    attribut("labelfontcolor", ValueType::Color, EDGE, Some("black")),
+    attribut("labelfontname", ValueType::String, EDGE, Some("Times-Roman")),
+    attribut("labelfontsize", ValueType::Double, EDGE, Some("14")),
+];
+
+pub fn lookup(name: &str) -> Option<&'static AttributSchema> {
+    SCHEMA.iter().find(|schema| schema.name == name)
+}
+
+impl AttributSchema {
+    // Clusters take the attributes of subgraphs too
+    pub fn applies_to(&self, element: Element) -> bool {
+        self.usage.contains(&element) || (element == Element::Cluster && self.usage.contains(&Element::Subgraph))
+    }
+
+    pub fn default_for(&self, element: Element) -> Option<&'static str> {
+        self.default.filter(|_| self.applies_to(element))
+    }
+
+    pub fn accepts(&self, value: &AttributValue) -> bool {
+        self.value_type.accepts(value)
+    }
+}
+
+impl ValueType {
+    pub fn accepts(&self, value: &AttributValue) -> bool {
+        if value.is_html() {
+            return matches!(self, ValueType::Label | ValueType::String);
+        }
+        let text = value.as_str().trim();
+        match self {
+            ValueType::String | ValueType::Label => true,
+            ValueType::Double => value.as_f64().is_some(),
+            ValueType::Int => text.parse::<i64>().is_ok(),
+            ValueType::Bool => value.as_bool().is_some(),
+            ValueType::Color => text.split(':').all(|color| is_color(color.split(';').next().unwrap_or_default())),
+            ValueType::Point => is_point(text),
+            ValueType::Style => text.split(',').all(|style| STYLES.contains(&style.split('(').next().unwrap_or_default().trim())),
+            ValueType::Arrow => is_arrow(text),
+            ValueType::Enum(values) => values.iter().any(|allowed| allowed.eq_ignore_ascii_case(text)),
+        }
+    }
+}
+
+impl Display for ValueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueType::String => write!(f, "a string"),
+            ValueType::Label => write!(f, "a label"),
+            ValueType::Double => write!(f, "a number"),
+            ValueType::Int => write!(f, "an integer"),
+            ValueType::Bool => write!(f, "a boolean"),
+            ValueType::Color => write!(f, "a color"),
+            ValueType::Point => write!(f, "a point"),
+            ValueType::Style => write!(f, "a style"),
+            ValueType::Arrow => write!(f, "an arrow type"),
+            ValueType::Enum(values) => write!(f, "one of {}", values.join(", ")),
+        }
+    }
+}
+
+impl Display for Element {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Element::Graph => "graph",
+            Element::Subgraph => "subgraph",
+            Element::Cluster => "cluster",
+            Element::Node => "node",
+            Element::Edge => "edge",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+// Names, #rrggbb[aa] and "H,S,V" colors
+fn is_color(color: &str) -> bool {
+    let color = color.trim();
+    if let Some(hexa) = color.strip_prefix('#') {
+        return matches!(hexa.len(), 6 | 8) && hexa.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    let hsv = color.split(|c: char| c == ',' || c.is_whitespace()).filter(|part| !part.is_empty()).collect::<Vec<_>>();
+    match hsv.len() {
+        3 => hsv.iter().all(|part| part.parse::<f64>().is_ok()),
+        1 => color.chars().all(|c| c.is_ascii_alphanumeric() || c == '/' || c == '_'),
+        _ => false
+    }
+}
+
+fn is_point(point: &str) -> bool {
+    let coordinates = point.strip_suffix('!').unwrap_or(point).split(',').collect::<Vec<_>>();
+    (1..=3).contains(&coordinates.len()) && coordinates.iter().all(|coordinate| coordinate.trim().parse::<f64>().is_ok())
+}
+
+// Up to four shapes, each with an optional 'o' and 'l' or 'r' modifier, as in "lteeoldiamond"
+fn is_arrow(arrow: &str) -> bool {
+    if LEGACY_ARROWS.contains(&arrow) {
+        return true;
+    }
+    let mut rest = arrow;
+    let mut shapes = 0;
+    while !rest.is_empty() && shapes < 4 {
+        let modifiers = rest.strip_prefix('o').unwrap_or(rest);
+        let modifiers = modifiers.strip_prefix(['l', 'r']).unwrap_or(modifiers);
+        match ARROWS.iter().find(|shape| modifiers.starts_with(*shape)) {
+            Some(shape) => rest = &modifiers[shape.len()..],
+            None => return false
+        }
+        shapes += 1;
+    }
+    rest.is_empty() && shapes > 0
+}
+
+// Problems of the attributes set on one element, described as "node A", "edge A -> B" and so on
+pub(crate) fn validate(attributs: &Attributs, element: Element, description: &str) -> Vec<ValidationError> {
+    attributs.iter().filter_map(|(name, value)| {
+        let Some(schema) = lookup(name) else {
+            return Some(ValidationError::UnknownAttribut { element: description.to_string(), name: name.clone() });
+        };
+        if !schema.applies_to(element) {
+            return Some(ValidationError::WrongElement { element: description.to_string(), name: name.clone() });
+        }
+        match schema.accepts(value) {
+            true => None,
+            false => Some(ValidationError::InvalidValue { element: description.to_string(), name: name.clone(), value: value.raw().to_string(), expected: schema.value_type.to_string() })
+        }
+    }).collect()
+}
+
+// Value set on the element, or the Graphviz default when there is none
+pub(crate) fn text<'a>(attributs: &'a Attributs, name: &str, element: Element) -> Option<&'a str> {
+    attributs.get_str(name).or_else(|| lookup(name).and_then(|schema| schema.default_for(element)))
+}
+
+pub(crate) fn number(attributs: &Attributs, name: &str, element: Element) -> Option<f64> {
+    match attributs.get_value(name) {
+        Some(value) => value.as_f64(),
+        None => text(attributs, name, element).and_then(|value| value.parse().ok())
+    }
+}
+
+pub(crate) fn list<'a>(attributs: &'a Attributs, name: &str) -> Vec<&'a str> {
+    attributs.get_str(name)
+        .map(|values| values.split(',').map(str::trim).filter(|value| !value.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+pub(crate) fn boolean(attributs: &Attributs, name: &str, element: Element) -> Option<bool> {
+    match attributs.get_value(name) {
+        Some(value) => value.as_bool(),
+        None => text(attributs, name, element).map(AttributValue::from).and_then(|value| value.as_bool())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_ok() {
+        let combinations :Vec<(&str, AttributValue, bool)> = vec![
+            ("shape", AttributValue::from("box"), true),
+            ("shape", AttributValue::from("Mdiamond"), true),
+            ("shape", AttributValue::from("blob"), false),
+            ("penwidth", AttributValue::from("2.5"), true),
+            ("penwidth", AttributValue::from("thick"), false),
+            ("minlen", AttributValue::from("1.5"), false),
+            ("constraint", AttributValue::from("false"), true),
+            ("constraint", AttributValue::from("maybe"), false),
+            ("color", AttributValue::from("#ff0000"), true),
+            ("color", AttributValue::from("red:blue;0.3"), true),
+            ("color", AttributValue::from("0.5 0.2 1.0"), true),
+            ("color", AttributValue::from("/blues9/3"), true),
+            ("color", AttributValue::from("#ff00"), false),
+            ("size", AttributValue::from("7.5,10!"), true),
+            ("size", AttributValue::from("big"), false),
+            ("style", AttributValue::from("filled,rounded"), true),
+            ("style", AttributValue::from("setlinewidth(2)"), true),
+            ("style", AttributValue::from("wavy"), false),
+            ("arrowhead", AttributValue::from("lteeoldiamond"), true),
+            ("arrowhead", AttributValue::from("onormal"), true),
+            ("arrowhead", AttributValue::from("ediamond"), true),
+            ("arrowhead", AttributValue::from("arrow"), false),
+            ("label", AttributValue::Html("<b>x</b>".to_string()), true),
+            ("shape", AttributValue::Html("box".to_string()), false),
+            ("rankdir", AttributValue::from("lr"), true),
+            ];
+
+        combinations.iter().for_each(|combinaisons| assert_eq!(lookup(combinaisons.0).unwrap().accepts(&combinaisons.1), combinaisons.2, "{}={}", combinaisons.0, combinaisons.1));
+    }
+
+    #[test]
+    fn validate_ko() {
+        let attributs = Attributs::try_from(&"shape=blob, rankdir=LR, colour=red, color=blue".to_string()).unwrap();
+        assert_eq!(validate(&attributs, Element::Node, "node A"), vec![
+            ValidationError::InvalidValue { element: "node A".to_string(), name: "shape".to_string(), value: "blob".to_string(), expected: ValueType::Enum(SHAPES).to_string() },
+            ValidationError::WrongElement { element: "node A".to_string(), name: "rankdir".to_string() },
+            ValidationError::UnknownAttribut { element: "node A".to_string(), name: "colour".to_string() },
+            ]);
+    }
+
+    #[test]
+    fn typed_values_fall_back_to_defaults() {
+        let attributs = Attributs::try_from(&"penwidth=\"2\", constraint=no".to_string()).unwrap();
+        assert_eq!(number(&attributs, "penwidth", Element::Edge), Some(2.0));
+        assert_eq!(number(&attributs, "arrowsize", Element::Edge), Some(1.0));
+        assert_eq!(boolean(&attributs, "constraint", Element::Edge), Some(false));
+        assert_eq!(text(&attributs, "shape", Element::Node), Some("ellipse"));
+        assert_eq!(text(&attributs, "shape", Element::Edge), None);
+        assert_eq!(text(&attributs, "rank", Element::Cluster), None);
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt::Display;
+
+// Location of a piece of DOT content, line and column start at 1
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct Span {
+    pub offset: usize,
+    pub length: usize,
+    pub line: usize,
+    pub column: usize
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+// Byte offsets of every line start, to turn offsets into spans
+pub struct LineIndex<'a> {
+    content: &'a str,
+    line_starts: Vec<usize>
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(content: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(position, _)| position + 1))
+            .collect();
+        LineIndex { content, line_starts }
+    }
+
+    pub fn span(&self, offset: usize, length: usize) -> Span {
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let column = self.content.get(line_start..offset)
+            .map(|before| before.chars().count())
+            .unwrap_or(offset - line_start) + 1;
+        Span { offset, length, line, column }
+    }
+
+    pub fn end(&self) -> Span {
+        self.span(self.content.len(), 0)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn span_ok() {
+        let index = LineIndex::new("digraph {\r\n  A -> B\n\télé; C\n");
+        let combinations :Vec<(usize, (usize, usize))> = vec![
+            (0, (1, 1)),
+            (8, (1, 9)),
+            (13, (2, 3)),
+            (20, (3, 1)),
+            (28, (3, 7)),
+            (30, (4, 1)),
+            ];
+
+        combinations.iter().for_each(|combinaisons| {
+            let span = index.span(combinaisons.0, 1);
+            assert_eq!((span.line, span.column), combinaisons.1);
+        });
+    }
+}
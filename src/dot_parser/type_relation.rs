@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use super::parsing_error::ParsingError;
 
 #[derive(Eq, PartialEq, Debug,Clone)]
@@ -12,11 +14,11 @@ pub enum TypeRelation {
 
 
 
-impl ToString for TypeRelation {
-    fn to_string(&self) -> String {
+impl Display for TypeRelation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TypeRelation::Oriente => "->".to_string(),
-            TypeRelation::NonOriente => "--".to_string(),
+            TypeRelation::Oriente => write!(f, "->"),
+            TypeRelation::NonOriente => write!(f, "--"),
         }
     }
 }
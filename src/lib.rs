@@ -1,8 +1,16 @@
 mod dot_parser; 
 
 pub use dot_parser::dot_graph::DotGraph;
-pub use dot_parser::node::Node;
+pub use dot_parser::graph_type::GraphType;
+pub use dot_parser::builder::{DotGraphBuilder, BuildError};
+pub use dot_parser::index::GraphIndex;
+pub use dot_parser::provenance::{EffectiveAttribut, EffectiveAttributs, Provenance};
+pub use dot_parser::node::{Node, GraphNode};
 pub use dot_parser::attributs::Attributs;
+pub use dot_parser::attribut_value::AttributValue;
 pub use dot_parser::edge::Edge;
 pub use dot_parser::type_relation::TypeRelation;
-pub use dot_parser::parsing_error::ParsingError;
\ No newline at end of file
+pub use dot_parser::parsing_error::ParsingError;
+pub use dot_parser::span::Span;
+pub use dot_parser::endpoint::{Endpoint, CompassPoint};
+pub use dot_parser::schema::{AttributSchema, Element, ValidationError, ValueType};
\ No newline at end of file